@@ -1,6 +1,15 @@
+pub mod arc;
 pub mod circle;
 pub mod ellipse;
+pub mod line;
+pub mod path;
+pub mod pie;
 pub mod polygon;
+pub mod polyline;
 pub mod rectangle;
+pub mod regular_polygon;
+pub mod ring;
+pub mod star;
 pub mod text;
+pub mod text_input;
 pub mod triangle;
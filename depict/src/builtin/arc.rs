@@ -0,0 +1,135 @@
+use std::f32::consts::{PI, TAU};
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, FillRule, LineCap},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{self, arc, circle_bounds, draw, TOLERANCE},
+    },
+};
+
+// Band along part of a circle, centered on the radius. Angles are in radians, starting at +x and
+// growing clockwise like in Circle, and a full turn or more closes it without caps.
+shape!(
+    pub struct Arc {
+        x: f32,
+        y: f32,
+        radius: f32,
+        start: f32,
+        end: f32,
+        thickness: f32 = 1.0,
+        background: Background = Background::Color(Color::CLEAR),
+        cap: LineCap = LineCap::Butt,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Arc {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
+        let sweep = (self.end - self.start).clamp(-TAU, TAU);
+        let half = self.thickness / 2.0;
+        if half <= 0.0 {
+            return;
+        }
+
+        let outer = self.radius + half;
+        let inner = f32::max(self.radius - half, 0.0);
+        let circle = |radius: f32| {
+            let mut points = Vec::new();
+            arc(center, radius, 0.0, TAU, tolerance, &mut points);
+            points
+        };
+
+        let contours = if sweep.abs() >= TAU && inner > 0.0 {
+            tessellation::ring(circle(outer), circle(inner))
+        } else if sweep.abs() >= TAU {
+            vec![circle(outer)]
+        } else {
+            vec![self.outline(center, sweep, tolerance)]
+        };
+
+        let area = circle_bounds(center, outer);
+        draw(
+            batch,
+            &contours,
+            FillRule::NonZero,
+            &self.background,
+            area,
+            feather,
+        );
+    }
+}
+
+impl Arc {
+    // Along the outer edge to the end, around its cap, back along the inner edge and around the
+    // cap at the start
+    fn outline(&self, center: Vec2, sweep: f32, tolerance: f32) -> Vec<Vec2> {
+        let half = self.thickness / 2.0;
+        let (outer, inner) = (self.radius + half, f32::max(self.radius - half, 0.0));
+        let end = self.start + sweep;
+
+        // Which way the caps bulge, along the arc at the end and back along it at the start
+        let turn = if sweep < 0.0 { -PI } else { PI };
+        let forward = Vec2::from_angle(end).perp() * turn.signum();
+        let backward = -Vec2::from_angle(self.start).perp() * turn.signum();
+
+        let mut points = vec![center + Vec2::from_angle(self.start) * outer];
+        arc(center, outer, self.start, sweep, tolerance, &mut points);
+
+        let (outer_end, inner_end) = (
+            center + Vec2::from_angle(end) * outer,
+            center + Vec2::from_angle(end) * inner,
+        );
+        match self.cap {
+            LineCap::Butt => points.push(inner_end),
+            LineCap::Square => {
+                points.push(outer_end + forward * half);
+                points.push(inner_end + forward * half);
+                points.push(inner_end);
+            }
+            LineCap::Round => {
+                let middle = center + Vec2::from_angle(end) * self.radius;
+                arc(middle, half, end, turn, tolerance, &mut points);
+            }
+        }
+
+        arc(center, inner, end, -sweep, tolerance, &mut points);
+
+        let (inner_start, outer_start) = (
+            center + Vec2::from_angle(self.start) * inner,
+            center + Vec2::from_angle(self.start) * outer,
+        );
+        match self.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                points.push(inner_start + backward * half);
+                points.push(outer_start + backward * half);
+            }
+            LineCap::Round => {
+                let middle = center + Vec2::from_angle(self.start) * self.radius;
+                arc(middle, half, self.start + PI, turn, tolerance, &mut points);
+                points.pop();
+            }
+        }
+
+        // A band as thick as its diameter has its inner edge all at the center
+        points.dedup();
+        points
+    }
+}
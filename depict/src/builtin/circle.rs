@@ -1,11 +1,16 @@
 use std::f32::consts::TAU;
 
 use depict_macro::shape;
+use glam::Vec2;
 
 use crate::{
-    core::renderable::Renderable,
-    engine::{properties::Properties, renderer::RenderBatch, shader::Vertex},
-    graphics::{asset::Assets, color::Color},
+    component::bounds::BoundingBox,
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{asset::Assets, color::Color, gradient, tessellation::draw_bordered},
 };
 
 shape!(
@@ -13,43 +18,45 @@ shape!(
         x: f32,
         y: f32,
         radius: f32,
-        color: Color = Color::CLEAR,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
         resolution: f32 = 10.0,
+        antialias: Option<bool> = None,
     }
 );
 
 impl Renderable for Circle {
-    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
-
-    fn render(&self, batch: &mut RenderBatch, _properties: &Properties) {
-        if self.color == Color::CLEAR {
-            return;
-        }
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
 
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
         let segments = u32::max(f32::sqrt(self.radius * self.resolution) as u32, 10);
-        for i in 0..segments {
-            let start_angle = TAU / segments as f32 * i as f32;
-            let end_angle = TAU / segments as f32 * (i + 1) as f32;
+        let contour: Vec<_> = (0..segments)
+            .map(|i| {
+                let angle = TAU / segments as f32 * i as f32;
+                center + Vec2::new(angle.cos(), angle.sin()) * self.radius
+            })
+            .collect();
+
+        // The whole circle rather than its outline, so images aren't cropped by the segments
+        let area = BoundingBox::new(
+            self.x - self.radius,
+            self.y - self.radius,
+            self.radius * 2.0,
+            self.radius * 2.0,
+        );
 
-            batch.triangle(
-                Vertex::new(self.x, self.y, 0.0, 0.0, self.color, u32::MAX),
-                Vertex::new(
-                    self.x + self.radius * start_angle.cos(),
-                    self.y + self.radius * start_angle.sin(),
-                    0.0,
-                    0.0,
-                    self.color,
-                    u32::MAX,
-                ),
-                Vertex::new(
-                    self.x + self.radius * end_angle.cos(),
-                    self.y + self.radius * end_angle.sin(),
-                    0.0,
-                    0.0,
-                    self.color,
-                    u32::MAX,
-                ),
-            );
-        }
+        draw_bordered(
+            batch,
+            &[contour],
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
     }
 }
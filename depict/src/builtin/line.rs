@@ -0,0 +1,53 @@
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, LineCap, Stroke},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        tessellation::{self, bounds, hairline, paint, TOLERANCE},
+    },
+};
+
+shape!(
+    pub struct Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        width: f32 = 1.0,
+        color: Color = Color::BLACK,
+        cap: LineCap = LineCap::Butt,
+        dashes: Vec<f32> = Vec::new(),
+        dash_offset: f32 = 0.0,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Line {
+    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        if self.color == Color::CLEAR {
+            return;
+        }
+
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let (width, color) = hairline(self.width, self.color, feather);
+
+        let stroke = Stroke::new(width, color)
+            .with_cap(self.cap)
+            .with_dashes(self.dashes.clone(), self.dash_offset);
+        let points = vec![Vec2::new(self.x1, self.y1), Vec2::new(self.x2, self.y2)];
+
+        let triangles = tessellation::stroke(&[(points, false)], &stroke, tolerance, feather);
+        let area = bounds(triangles.iter().flatten().map(|(point, _)| *point));
+        paint(batch, &triangles, &Background::Color(color), area);
+    }
+}
@@ -0,0 +1,224 @@
+use std::mem;
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, FillRule, Stroke},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{self, arc, bounds, cubic, draw, hairline, paint, quad, TOLERANCE},
+    },
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    ArcTo(f32, f32, f32, f32, f32),
+    Close,
+}
+
+shape!(
+    pub struct Path {
+        commands: Vec<PathCommand> = Vec::new(),
+        // Unclosed subpaths are filled as if they were closed
+        fill: Background = Background::Color(Color::CLEAR),
+        fill_rule: FillRule = FillRule::NonZero,
+        stroke: Option<Stroke> = None,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Path {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.fill, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let subpaths = self.flatten(tolerance);
+
+        let contours: Vec<_> = subpaths
+            .iter()
+            .filter(|(points, _)| points.len() >= 3)
+            .map(|(points, _)| points.clone())
+            .collect();
+        if !contours.is_empty() {
+            let area = bounds(contours.iter().flatten().copied());
+            draw(batch, &contours, self.fill_rule, &self.fill, area, feather);
+        }
+
+        if let Some(stroke) = &self.stroke {
+            if stroke.color == Color::CLEAR {
+                return;
+            }
+
+            let (width, color) = hairline(stroke.width, stroke.color, feather);
+            let stroke = Stroke {
+                width,
+                color,
+                ..stroke.clone()
+            };
+
+            let triangles = tessellation::stroke(&subpaths, &stroke, tolerance, feather);
+            let area = bounds(triangles.iter().flatten().map(|(point, _)| *point));
+            paint(batch, &triangles, &Background::Color(color), area);
+        }
+    }
+}
+
+impl Path {
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::MoveTo(x, y));
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::LineTo(x, y));
+        self
+    }
+
+    pub fn quad_to(mut self, control_x: f32, control_y: f32, x: f32, y: f32) -> Self {
+        self.commands
+            .push(PathCommand::QuadTo(control_x, control_y, x, y));
+        self
+    }
+
+    pub fn cubic_to(
+        mut self,
+        first_x: f32,
+        first_y: f32,
+        second_x: f32,
+        second_y: f32,
+        x: f32,
+        y: f32,
+    ) -> Self {
+        self.commands.push(PathCommand::CubicTo(
+            first_x, first_y, second_x, second_y, x, y,
+        ));
+        self
+    }
+
+    // Rounds the corner at (x1, y1) between the current point and (x2, y2), like arcTo on a canvas
+    pub fn arc_to(mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) -> Self {
+        self.commands
+            .push(PathCommand::ArcTo(x1, y1, x2, y2, radius));
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    // Subpaths as points, and whether each was closed
+    fn flatten(&self, tolerance: f32) -> Vec<(Vec<Vec2>, bool)> {
+        let mut subpaths = Vec::new();
+        let mut current: Vec<Vec2> = Vec::new();
+        let mut start = None;
+
+        for command in &self.commands {
+            // Drawing after a close continues from where that subpath started, and drawing
+            // before any move starts from the command's first point
+            let first = match *command {
+                PathCommand::MoveTo(x, y)
+                | PathCommand::LineTo(x, y)
+                | PathCommand::QuadTo(x, y, ..)
+                | PathCommand::CubicTo(x, y, ..)
+                | PathCommand::ArcTo(x, y, ..) => Vec2::new(x, y),
+                PathCommand::Close => Vec2::ZERO,
+            };
+            let draws = !matches!(command, PathCommand::MoveTo(..) | PathCommand::Close);
+            if current.is_empty() && draws {
+                current.push(*start.get_or_insert(first));
+            }
+            let from = current.last().copied().unwrap_or(first);
+
+            match *command {
+                PathCommand::MoveTo(x, y) => {
+                    if current.len() > 1 {
+                        subpaths.push((mem::take(&mut current), false));
+                    }
+                    current.clear();
+
+                    let point = Vec2::new(x, y);
+                    start = Some(point);
+                    current.push(point);
+                }
+                PathCommand::LineTo(x, y) => current.push(Vec2::new(x, y)),
+                PathCommand::QuadTo(cx, cy, x, y) => quad(
+                    from,
+                    Vec2::new(cx, cy),
+                    Vec2::new(x, y),
+                    tolerance,
+                    &mut current,
+                ),
+                PathCommand::CubicTo(ax, ay, bx, by, x, y) => cubic(
+                    from,
+                    Vec2::new(ax, ay),
+                    Vec2::new(bx, by),
+                    Vec2::new(x, y),
+                    tolerance,
+                    &mut current,
+                ),
+                PathCommand::ArcTo(x1, y1, x2, y2, radius) => {
+                    corner(
+                        from,
+                        Vec2::new(x1, y1),
+                        Vec2::new(x2, y2),
+                        radius,
+                        tolerance,
+                        &mut current,
+                    );
+                }
+                PathCommand::Close => {
+                    if !current.is_empty() {
+                        subpaths.push((mem::take(&mut current), true));
+                    }
+                }
+            }
+        }
+
+        if current.len() > 1 {
+            subpaths.push((current, false));
+        }
+
+        subpaths
+    }
+}
+
+// Line to where a circle of the radius touches both legs of the corner, then around it
+fn corner(from: Vec2, corner: Vec2, to: Vec2, radius: f32, tolerance: f32, points: &mut Vec<Vec2>) {
+    let before = (from - corner).normalize_or_zero();
+    let after = (to - corner).normalize_or_zero();
+    if radius <= 0.0 || before.perp_dot(after).abs() < 1e-4 {
+        points.push(corner);
+        return;
+    }
+
+    let half = before.dot(after).clamp(-1.0, 1.0).acos() / 2.0;
+    let center = corner + (before + after).normalize() * radius / half.sin();
+    let first = corner + before * radius / half.tan();
+    let second = corner + after * radius / half.tan();
+
+    points.push(first);
+    let (start, end) = (first - center, second - center);
+    arc(
+        center,
+        radius,
+        start.y.atan2(start.x),
+        start.perp_dot(end).atan2(start.dot(end)),
+        tolerance,
+        points,
+    );
+}
@@ -0,0 +1,73 @@
+use std::f32::consts::TAU;
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{arc, circle_bounds, draw_bordered, TOLERANCE},
+    },
+};
+
+// Slice of a circle between two angles, in radians like in Arc
+shape!(
+    pub struct Pie {
+        x: f32,
+        y: f32,
+        radius: f32,
+        start: f32,
+        end: f32,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Pie {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
+
+        // Swept clockwise, so borders know which side is out
+        let (mut start, mut sweep) = (self.start, (self.end - self.start).clamp(-TAU, TAU));
+        if sweep < 0.0 {
+            (start, sweep) = (start + sweep, -sweep);
+        }
+        if sweep <= 0.0 {
+            return;
+        }
+
+        // A full turn is a circle, without the center
+        let mut contour = Vec::new();
+        if sweep < TAU {
+            contour.push(center);
+            contour.push(center + Vec2::from_angle(start) * self.radius);
+        }
+        arc(center, self.radius, start, sweep, tolerance, &mut contour);
+
+        let area = circle_bounds(center, self.radius);
+
+        draw_bordered(
+            batch,
+            &[contour],
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
+    }
+}
@@ -1,33 +1,79 @@
+use depict_macro::shape;
+use glam::Vec2;
+
 use crate::{
-    component::id::RENDERABLE_ID_FACTORY,
-    core::renderable::Renderable,
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
     engine::{properties::Properties, renderer::RenderBatch},
-    graphics::asset::Assets,
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{bounds, draw_bordered, signed_area},
+    },
 };
 
-pub struct Polygon {
-    pub(crate) id: u32,
-    pub points: Vec<(f32, f32)>,
-}
+shape!(
+    pub struct Polygon {
+        points: Vec<(f32, f32)>,
+        // Cut out of the polygon whichever way they wind
+        holes: Vec<Vec<(f32, f32)>> = Vec::new(),
+        fill_rule: FillRule = FillRule::NonZero,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
+    }
+);
 
 impl Renderable for Polygon {
-    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
-
-    fn render(&self, batch: &mut RenderBatch, _properties: &Properties) {
-        todo!()
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
     }
-}
 
-impl Polygon {
-    pub fn new(x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32) -> Self {
-        Self {
-            id: unsafe { RENDERABLE_ID_FACTORY.next() },
-            points: vec![(x1, y1), (x2, y2), (x3, y3)],
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        if self.points.len() < 3 {
+            return;
+        }
+
+        // Wound with a positive area, and holes the other way, so borders know which side is out
+        let mut outline: Vec<_> = self.points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+        if signed_area(&outline) < 0.0 {
+            outline.reverse();
         }
+
+        let mut contours = vec![outline];
+        for hole in &self.holes {
+            let mut hole: Vec<_> = hole.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+            if signed_area(&hole) > 0.0 {
+                hole.reverse();
+            }
+            contours.push(hole);
+        }
+
+        let feather = properties.feather(self.antialias);
+        let area = bounds(contours[0].iter().copied());
+        draw_bordered(
+            batch,
+            &contours,
+            self.fill_rule,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
     }
+}
 
+impl Polygon {
     pub fn with_point(mut self, x: f32, y: f32) -> Self {
         self.points.push((x, y));
         self
     }
+
+    pub fn with_hole(mut self, points: Vec<(f32, f32)>) -> Self {
+        self.holes.push(points);
+        self
+    }
 }
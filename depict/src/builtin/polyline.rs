@@ -0,0 +1,63 @@
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, LineCap, LineJoin, Stroke},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        tessellation::{self, bounds, hairline, paint, TOLERANCE},
+    },
+};
+
+shape!(
+    pub struct Polyline {
+        points: Vec<(f32, f32)>,
+        width: f32 = 1.0,
+        color: Color = Color::BLACK,
+        join: LineJoin = LineJoin::Miter,
+        cap: LineCap = LineCap::Butt,
+        miter_limit: f32 = 4.0,
+        // Joins the last point back to the first, leaving no ends to cap
+        closed: bool = false,
+        dashes: Vec<f32> = Vec::new(),
+        dash_offset: f32 = 0.0,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Polyline {
+    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        if self.color == Color::CLEAR || self.points.is_empty() {
+            return;
+        }
+
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let (width, color) = hairline(self.width, self.color, feather);
+
+        let stroke = Stroke::new(width, color)
+            .with_join(self.join)
+            .with_cap(self.cap)
+            .with_miter_limit(self.miter_limit)
+            .with_dashes(self.dashes.clone(), self.dash_offset);
+        let points = self.points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+
+        let triangles = tessellation::stroke(&[(points, self.closed)], &stroke, tolerance, feather);
+        let area = bounds(triangles.iter().flatten().map(|(point, _)| *point));
+        paint(batch, &triangles, &Background::Color(color), area);
+    }
+}
+
+impl Polyline {
+    pub fn with_point(mut self, x: f32, y: f32) -> Self {
+        self.points.push((x, y));
+        self
+    }
+}
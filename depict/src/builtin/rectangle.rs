@@ -1,9 +1,19 @@
 use depict_macro::shape;
+use glam::Vec2;
 
 use crate::{
-    core::{properties::Background, renderable::Renderable},
-    engine::{properties::Properties, renderer::RenderBatch, shader::Vertex},
-    graphics::{asset::Assets, color::Color},
+    component::bounds::BoundingBox,
+    core::{
+        properties::{Background, Border, CornerRadius, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{draw, ring, rounded, TOLERANCE},
+    },
 };
 
 shape!(
@@ -13,88 +23,91 @@ shape!(
         width: f32,
         height: f32,
         background: Background = Background::Color(Color::CLEAR),
-        // border: Border = Border::NONE,
+        border: Border = Border::NONE,
+        corner_radius: CornerRadius = CornerRadius::NONE,
+        antialias: Option<bool> = None,
     }
 );
 
 impl Renderable for Rectangle {
-    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
 
-    fn render(&self, batch: &mut RenderBatch, _properties: &Properties) {
-        match self.background {
-            Background::Color(color) => {
-                if color == Color::CLEAR {
-                    return;
-                }
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let bounds = BoundingBox::new(self.x, self.y, self.width, self.height);
 
-                batch.triangle(
-                    Vertex::new(self.x, self.y, 0.0, 0.0, color, u32::MAX),
-                    Vertex::new(self.x + self.width, self.y, 0.0, 0.0, color, u32::MAX),
-                    Vertex::new(self.x, self.y + self.height, 0.0, 0.0, color, u32::MAX),
-                );
-                batch.triangle(
-                    Vertex::new(self.x + self.width, self.y, 0.0, 0.0, color, u32::MAX),
-                    Vertex::new(self.x, self.y + self.height, 0.0, 0.0, color, u32::MAX),
-                    Vertex::new(
-                        self.x + self.width,
-                        self.y + self.height,
-                        0.0,
-                        0.0,
-                        color,
-                        u32::MAX,
-                    ),
-                );
-            }
-            Background::Image(asset) => {
-                let image = batch.assets.images.get(asset.id).clone();
+        draw(
+            batch,
+            &[self.outline(0.0, tolerance)],
+            FillRule::NonZero,
+            &self.background,
+            bounds,
+            feather,
+        );
 
-                batch.triangle(
-                    Vertex::new(self.x, self.y, image.u, image.v, Color::CLEAR, 0),
-                    Vertex::new(
-                        self.x + self.width,
-                        self.y,
-                        image.u + image.width,
-                        image.v,
-                        Color::CLEAR,
-                        0,
-                    ),
-                    Vertex::new(
-                        self.x,
-                        self.y + self.height,
-                        image.u,
-                        image.v + image.height,
-                        Color::CLEAR,
-                        0,
-                    ),
-                );
+        if self.border.thickness > 0.0 {
+            let (inner, outer) = self.border.extent();
+            let contours = ring(
+                self.outline(outer, tolerance),
+                self.outline(inner, tolerance),
+            );
+            draw(
+                batch,
+                &contours,
+                FillRule::NonZero,
+                &Background::Color(self.border.color),
+                bounds,
+                feather,
+            );
+        }
+    }
+}
 
-                batch.triangle(
-                    Vertex::new(
-                        self.x + self.width,
-                        self.y,
-                        image.u + image.width,
-                        image.v,
-                        Color::CLEAR,
-                        0,
-                    ),
-                    Vertex::new(
-                        self.x,
-                        self.y + self.height,
-                        image.u,
-                        image.v + image.height,
-                        Color::CLEAR,
-                        0,
-                    ),
-                    Vertex::new(
-                        self.x + self.width,
-                        self.y + self.height,
-                        image.u + image.width,
-                        image.v + image.height,
-                        Color::CLEAR,
-                        0,
-                    ),
-                );
+impl Rectangle {
+    // Grown by the distance, or shrunk when it's negative, with rounded corners following along
+    // until they turn sharp
+    fn outline(&self, distance: f32, tolerance: f32) -> Vec<Vec2> {
+        let bounds = BoundingBox::new(
+            self.x - distance,
+            self.y - distance,
+            f32::max(self.width + distance * 2.0, 0.0),
+            f32::max(self.height + distance * 2.0, 0.0),
+        );
+        let radii = self.radii().map(|radius| {
+            if radius > 0.0 {
+                f32::max(radius + distance, 0.0)
+            } else {
+                0.0
             }
-        }
+        });
+
+        rounded(bounds, radii, tolerance)
+    }
+
+    fn radii(&self) -> [f32; 4] {
+        let CornerRadius {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        } = self.corner_radius;
+        let radii = [top_left, top_right, bottom_right, bottom_left].map(|r| f32::max(r, 0.0));
+
+        let sides = [
+            (self.width, radii[0] + radii[1]),
+            (self.height, radii[1] + radii[2]),
+            (self.width, radii[2] + radii[3]),
+            (self.height, radii[3] + radii[0]),
+        ];
+        let scale = sides
+            .iter()
+            .filter(|(_, sum)| *sum > 0.0)
+            .map(|(length, sum)| length.abs() / sum)
+            .fold(1.0, f32::min);
+
+        radii.map(|radius| radius * scale)
     }
 }
@@ -0,0 +1,65 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{circle_bounds, draw_bordered},
+    },
+};
+
+// Polygon with equal sides, with corners on the radius. Unrotated, the first corner points up, and
+// rotation turns it clockwise in radians.
+shape!(
+    pub struct RegularPolygon {
+        x: f32,
+        y: f32,
+        radius: f32,
+        sides: u32,
+        rotation: f32 = 0.0,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for RegularPolygon {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        if self.sides < 3 {
+            return;
+        }
+
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
+        let contour: Vec<_> = (0..self.sides)
+            .map(|i| {
+                let angle = self.rotation - FRAC_PI_2 + TAU / self.sides as f32 * i as f32;
+                center + Vec2::from_angle(angle) * self.radius
+            })
+            .collect();
+
+        let area = circle_bounds(center, self.radius);
+        draw_bordered(
+            batch,
+            &[contour],
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::f32::consts::TAU;
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{self, arc, circle_bounds, draw_bordered, TOLERANCE},
+    },
+};
+
+// Circle with a hole in the middle. Borders go around both edges, like Polygon holes.
+shape!(
+    pub struct Ring {
+        x: f32,
+        y: f32,
+        inner_radius: f32,
+        outer_radius: f32,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Ring {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let tolerance = TOLERANCE / properties.scale_factor;
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
+        if self.outer_radius <= 0.0 {
+            return;
+        }
+
+        let circle = |radius: f32| {
+            let mut points = Vec::new();
+            arc(center, radius, 0.0, TAU, tolerance, &mut points);
+            points
+        };
+
+        let contours = if self.inner_radius > 0.0 {
+            tessellation::ring(circle(self.outer_radius), circle(self.inner_radius))
+        } else {
+            vec![circle(self.outer_radius)]
+        };
+
+        let area = circle_bounds(center, self.outer_radius);
+        draw_bordered(
+            batch,
+            &contours,
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use depict_macro::shape;
+use glam::Vec2;
+
+use crate::{
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{circle_bounds, draw_bordered},
+    },
+};
+
+// Points on the outer radius with dips to the inner one between them, turned like RegularPolygon
+shape!(
+    pub struct Star {
+        x: f32,
+        y: f32,
+        points: u32,
+        outer_radius: f32,
+        inner_radius: f32,
+        rotation: f32 = 0.0,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
+    }
+);
+
+impl Renderable for Star {
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
+
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        if self.points < 2 {
+            return;
+        }
+
+        let feather = properties.feather(self.antialias);
+        let center = Vec2::new(self.x, self.y);
+        let contour: Vec<_> = (0..self.points * 2)
+            .map(|i| {
+                let angle = self.rotation - FRAC_PI_2 + PI / self.points as f32 * i as f32;
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+                center + Vec2::from_angle(angle) * radius
+            })
+            .collect();
+
+        let radius = f32::max(self.outer_radius.abs(), self.inner_radius.abs());
+        let area = circle_bounds(center, radius);
+        draw_bordered(
+            batch,
+            &[contour],
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
+        );
+    }
+}
@@ -1,16 +1,27 @@
+use std::{mem, ops::Range};
+
 use depict_macro::shape;
+use glam::Vec2;
+use unicode_linebreak::linebreaks;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
+    builtin::rectangle::Rectangle,
+    component::bounds::BoundingBox,
     core::{
-        properties::{Align, OverflowBreak},
+        properties::{
+            Align, Background, Border, OverflowBreak, Shadow, TextOverflow, VerticalAlign,
+            WritingMode,
+        },
         renderable::Renderable,
     },
     engine::{properties::Properties, renderer::RenderBatch, shader::Vertex},
     graphics::{
-        asset::{Asset, Assets, Font},
+        asset::{Asset, Assets, Font, FontAsset},
         color::Color,
         font::{FontEmphasis, FontThickness},
-        glyph::{Glyph, TextRenderingData},
+        glyph::{is_newline, Decorations, Glyph, TextRenderingData, SOFT_HYPHEN},
+        markup::{self, MarkupError},
     },
 };
 
@@ -20,82 +31,438 @@ shape!(
         y: f32,
         text: String,
         font: Asset<Font>,
+        fallbacks: Vec<Asset<Font>> = Vec::new(),
         thickness: FontThickness = FontThickness::Regular,
         emphasis: FontEmphasis = FontEmphasis::Regular,
+        // Percentage of normal width, for variable fonts with a width axis
+        stretch: f32 = 100.0,
         size: f32 = 16.0,
         line_height: f32 = 1.2,
         width: Option<f32> = None,
-        // height: Option<f32> = None,
+        max_height: Option<f32> = None,
+        max_lines: Option<usize> = None,
+        overflow: TextOverflow = TextOverflow::Clip,
         align: Align = Align::Left,
+        vertical_align: VerticalAlign = VerticalAlign::Top,
         color: Color = Color::BLACK,
+        spans: Vec<TextSpan> = Vec::new(),
         overflow_break: OverflowBreak = OverflowBreak::Word,
         trim_whitespace: bool = true,
+        tab_size: f32 = 4.0,
+        writing_mode: WritingMode = WritingMode::Horizontal,
+        underline: bool = false,
+        strikethrough: bool = false,
+        outline: Border = Border::NONE,
+        shadow: Option<Shadow> = None,
     }
 );
 
+// Overrides for part of a Text, where unset fields inherit from the Text itself
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct TextStyle {
+    pub font: Option<Asset<Font>>,
+    pub thickness: Option<FontThickness>,
+    pub emphasis: Option<FontEmphasis>,
+    pub size: Option<f32>,
+    pub color: Option<Color>,
+    pub underline: Option<bool>,
+    pub strikethrough: Option<bool>,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font(mut self, font: Asset<Font>) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: FontThickness) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    pub fn with_emphasis(mut self, emphasis: FontEmphasis) -> Self {
+        self.emphasis = Some(emphasis);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_underline(mut self, underline: bool) -> Self {
+        self.underline = Some(underline);
+        self
+    }
+
+    pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = Some(strikethrough);
+        self
+    }
+
+    // Fields set on other win
+    pub fn merge(&self, other: &TextStyle) -> TextStyle {
+        TextStyle {
+            font: other.font.or(self.font),
+            thickness: other.thickness.or(self.thickness),
+            emphasis: other.emphasis.or(self.emphasis),
+            size: other.size.or(self.size),
+            color: other.color.or(self.color),
+            underline: other.underline.or(self.underline),
+            strikethrough: other.strikethrough.or(self.strikethrough),
+        }
+    }
+}
+
+// Styles the characters (not bytes) within range, with later spans taking priority
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub style: TextStyle,
+}
+
 #[derive(Debug, Clone)]
 struct Chunk {
+    start: usize,
     indices: Vec<usize>,
+    advances: Vec<f32>,
     width: f32,
 }
 
 impl Chunk {
-    fn new(indices: Vec<usize>, width: f32) -> Self {
-        Self { indices, width }
-    }
-
-    fn empty() -> Self {
+    fn empty(start: usize) -> Self {
         Self {
+            start,
             indices: Vec::new(),
+            advances: Vec::new(),
             width: 0.0,
         }
     }
 
-    fn reset(&mut self) {
-        self.indices.clear();
-        self.width = 0.0;
+    fn marker(start: usize, index: usize) -> Self {
+        Self {
+            start,
+            indices: vec![index],
+            advances: vec![0.0],
+            width: 0.0,
+        }
     }
 
-    fn add(&mut self, index: usize, width: f32) {
+    fn add(&mut self, index: usize, advance: f32) {
         self.indices.push(index);
-        self.width += width;
+        self.advances.push(advance);
+        self.width += advance;
     }
 
-    fn extend(&mut self, chunk: &Chunk) {
-        self.indices.extend(chunk.indices.clone());
-        self.width += chunk.width;
+    fn pop(&mut self) -> Option<usize> {
+        let advance = self.advances.pop()?;
+        self.width -= advance;
+        self.indices.pop()
     }
 
     fn is_empty(&self) -> bool {
         self.indices.is_empty() && self.width == 0.0
     }
 
-    fn flatten(chunks: &Vec<Chunk>) -> Chunk {
-        let mut accumulator = Chunk::empty();
-        for chunk in chunks {
-            accumulator.extend(&chunk);
+    // Lines produced for mandatory breaks, which only move the pen
+    fn is_break(&self, data: &TextRenderingData) -> bool {
+        self.indices.len() == 1 && is_newline(data.glyphs[self.indices[0]].character)
+    }
+}
+
+// Positions are along the line, as if the text were horizontal, except for origin which is where
+// the glyph is actually drawn from
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct PlacedGlyph {
+    pub index: usize,
+    pub x: f32,
+    pub baseline: f32,
+    pub advance: f32,
+    pub opacity: f32,
+    pub origin: Vec2,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LineLayout {
+    pub glyphs: Range<usize>,
+    // From the first character to where a caret at the end of the line sits
+    pub chars: Range<usize>,
+    pub bounds: BoundingBox,
+    pub baseline: f32,
+}
+
+// Where every glyph of a Text ended up, from Context::layout_text
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    pub(crate) glyphs: Vec<PlacedGlyph>,
+    pub(crate) lines: Vec<LineLayout>,
+    pub(crate) writing_mode: WritingMode,
+    pub(crate) origin: Vec2,
+    pub width: f32,
+    pub height: f32,
+    // The box given by width and max_height, or the text's own extent where they aren't set
+    pub bounds: BoundingBox,
+}
+
+impl TextLayout {
+    pub fn writing_mode(&self) -> WritingMode {
+        self.writing_mode
+    }
+
+    // Turns a box along the lines into screen space, the identity for horizontal text
+    pub(crate) fn to_screen(&self, rect: BoundingBox) -> BoundingBox {
+        let across = rect.y - self.origin.y;
+        let along = rect.x - self.origin.x;
+
+        match self.writing_mode {
+            WritingMode::Horizontal => rect,
+            WritingMode::VerticalRl => BoundingBox::new(
+                self.origin.x - across - rect.height,
+                self.origin.y + along,
+                rect.height,
+                rect.width,
+            ),
+            WritingMode::VerticalLr => BoundingBox::new(
+                self.origin.x + across,
+                self.origin.y + along,
+                rect.height,
+                rect.width,
+            ),
+        }
+    }
+
+    fn to_lines(&self, x: f32, y: f32) -> (f32, f32) {
+        match self.writing_mode {
+            WritingMode::Horizontal => (x, y),
+            WritingMode::VerticalRl => (
+                self.origin.x + y - self.origin.y,
+                self.origin.y + self.origin.x - x,
+            ),
+            WritingMode::VerticalLr => (
+                self.origin.x + y - self.origin.y,
+                self.origin.y + x - self.origin.x,
+            ),
+        }
+    }
+
+    // Line a caret at index sits on, preferring the start of a line over the end of the last
+    fn line_of(&self, index: usize) -> &LineLayout {
+        let after = self.lines.partition_point(|line| line.chars.start <= index);
+        &self.lines[after.saturating_sub(1)]
+    }
+
+    fn x_of(&self, line: &LineLayout, index: usize) -> f32 {
+        let glyphs = &self.glyphs[line.glyphs.clone()];
+        match glyphs.iter().find(|placed| placed.index >= index) {
+            Some(placed) => placed.x,
+            None => glyphs
+                .last()
+                .map_or(line.bounds.x, |placed| placed.x + placed.advance),
+        }
+    }
+
+    fn line_caret(&self, index: usize) -> BoundingBox {
+        let line = self.line_of(index);
+        BoundingBox::new(
+            self.x_of(line, index),
+            line.bounds.y,
+            0.0,
+            line.bounds.height,
+        )
+    }
+
+    fn line_index_at(&self, x: f32, y: f32) -> usize {
+        let line = self
+            .lines
+            .iter()
+            .find(|line| y < line.bounds.y + line.bounds.height)
+            .or(self.lines.last())
+            .expect("Text always has a line");
+
+        self.glyphs[line.glyphs.clone()]
+            .iter()
+            .find(|placed| x < placed.x + 0.5 * placed.advance)
+            .map_or(line.chars.end, |placed| {
+                placed.index.clamp(line.chars.start, line.chars.end)
+            })
+    }
+
+    // Box across the line the caret before the character at index sits on, with no width for
+    // horizontal text and no height for vertical text
+    pub fn caret(&self, index: usize) -> BoundingBox {
+        self.to_screen(self.line_caret(index))
+    }
+
+    // Character index of the caret position closest to a point
+    pub fn index_at(&self, x: f32, y: f32) -> usize {
+        let (x, y) = self.to_lines(x, y);
+        self.line_index_at(x, y)
+    }
+
+    // Closest caret position on the line before or after the one index is on
+    pub fn adjacent_line(&self, index: usize, forward: bool) -> usize {
+        let caret = self.line_caret(index);
+        let y = if forward {
+            caret.y + 1.5 * caret.height
+        } else {
+            caret.y - 0.5 * caret.height
+        };
+
+        self.line_index_at(caret.x, y)
+    }
+
+    pub fn line_chars(&self, index: usize) -> Range<usize> {
+        self.line_of(index).chars.clone()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    // One box per line covering the characters in range
+    pub fn selection(&self, range: Range<usize>) -> Vec<BoundingBox> {
+        if range.is_empty() {
+            return Vec::new();
         }
-        accumulator
+
+        self.lines
+            .iter()
+            .filter(|line| line.chars.start < range.end && range.start <= line.chars.end)
+            .filter_map(|line| {
+                let start = self.x_of(line, usize::max(range.start, line.chars.start));
+
+                // Selecting past the end of a line also covers its line break
+                let end = if range.end > line.chars.end {
+                    let glyphs = &self.glyphs[line.glyphs.clone()];
+                    let content = glyphs
+                        .last()
+                        .map_or(line.bounds.x, |placed| placed.x + placed.advance);
+                    f32::max(content, start + 0.25 * line.bounds.height)
+                } else {
+                    self.x_of(line, range.end)
+                };
+
+                (end > start).then(|| {
+                    self.to_screen(BoundingBox::new(
+                        start,
+                        line.bounds.y,
+                        end - start,
+                        line.bounds.height,
+                    ))
+                })
+            })
+            .collect()
     }
+
+    // Selection boxes as shapes, for drawing highlights behind the text
+    pub fn highlights(&self, range: Range<usize>, background: Background) -> Vec<Rectangle> {
+        self.selection(range)
+            .into_iter()
+            .map(|rect| {
+                Rectangle::new(rect.x, rect.y, rect.width, rect.height)
+                    .with_background(background.clone())
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextMeasurement {
+    pub width: f32,
+    pub height: f32,
+    pub line_count: usize,
+    pub lines: Vec<BoundingBox>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct DecorationLine {
+    rect: BoundingBox,
+    color: Color,
+    opacity: f32,
+    strikethrough: bool,
+}
+
+impl DecorationLine {
+    fn continues(&self, other: &DecorationLine) -> bool {
+        self.rect.y == other.rect.y
+            && self.rect.height == other.rect.height
+            && self.color == other.color
+            && self.opacity == other.opacity
+            && (self.rect.x + self.rect.width - other.rect.x).abs() < 0.5
+    }
+}
+
+fn grow(rect: BoundingBox, amount: f32) -> BoundingBox {
+    BoundingBox::new(
+        rect.x - amount,
+        rect.y - amount,
+        rect.width + 2.0 * amount,
+        rect.height + 2.0 * amount,
+    )
+}
+
+fn fade(color: Color, opacity: f32) -> Color {
+    Color::new(color.red, color.green, color.blue, color.alpha * opacity)
+}
+
+fn draw_rect(rect: BoundingBox, color: Color, batch: &mut RenderBatch) {
+    let (left, top) = (rect.x, rect.y);
+    let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+
+    batch.triangle(
+        Vertex::new(left, top, 0.0, 0.0, color, u32::MAX),
+        Vertex::new(right, top, 0.0, 0.0, color, u32::MAX),
+        Vertex::new(left, bottom, 0.0, 0.0, color, u32::MAX),
+    );
+    batch.triangle(
+        Vertex::new(right, top, 0.0, 0.0, color, u32::MAX),
+        Vertex::new(left, bottom, 0.0, 0.0, color, u32::MAX),
+        Vertex::new(right, bottom, 0.0, 0.0, color, u32::MAX),
+    );
 }
 
 impl Renderable for Text {
     fn request(&self, assets: &mut Assets, properties: &Properties) {
-        if self.color == Color::CLEAR {
+        if !self.visible() {
             return;
         }
 
-        let mut glyphs = Vec::new();
-        for character in self.text.chars() {
-            glyphs.push(Glyph {
-                character,
-                font_id: self.font.id,
-                image_id: u32::MAX,
-                size: self.size * properties.scale_factor,
-                color: self.color,
-                thickness: self.thickness,
-                emphasis: self.emphasis,
-            });
+        let mut glyphs = self.glyphs(&assets.fonts, properties);
+        let count = glyphs.len();
+        let outline = self.outline.thickness * properties.scale_factor;
+
+        // Outlines and shadows are rasterized separately, as layers after the text's own glyphs
+        if self.has_outline() {
+            let layer: Vec<_> = glyphs[..count]
+                .iter()
+                .map(|glyph| Glyph {
+                    color: self.outline.color,
+                    outline,
+                    ..*glyph
+                })
+                .collect();
+            glyphs.extend(layer);
+        }
+        if let Some(shadow) = self.visible_shadow() {
+            let layer: Vec<_> = glyphs[..count]
+                .iter()
+                .map(|glyph| Glyph {
+                    color: shadow.color,
+                    outline: if self.has_outline() { outline } else { 0.0 },
+                    blur: shadow.blur * properties.scale_factor,
+                    ..*glyph
+                })
+                .collect();
+            glyphs.extend(layer);
         }
 
         assets.fonts.data.insert(
@@ -107,201 +474,760 @@ impl Renderable for Text {
         );
     }
 
-    // TODO: Add support for vertical fonts
     fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
-        if self.color == Color::CLEAR {
+        if !self.visible() {
             return;
         }
 
-        let max_width = if let Some(width) = self.width {
-            width
-        } else {
-            f32::INFINITY
-        };
-
+        let scale = properties.scale_factor;
         let data = batch.assets.fonts.data[&self.id].clone();
-        let (lines, used_width) = self.lines(&data, max_width, properties.scale_factor);
+        let layout = self.layout(&data, scale);
+        let lines = self.decorations(&layout, &data);
 
-        let total_width = if let Some(width) = self.width {
-            width
-        } else {
-            used_width
-        };
+        let count = self.glyph_count();
+        let outline = self.outline.thickness * scale;
 
-        let size = self.size * properties.scale_factor;
-        let vertical_shift = size * self.line_height;
+        if let Some(shadow) = self.visible_shadow() {
+            let offset = Vec2::new(shadow.x, shadow.y) * scale;
+            let layer = count * (1 + self.has_outline() as usize);
+            self.draw_layer(&layout, &data, layer, offset, batch);
 
-        let mut calc_x = 0.0;
-        let mut calc_y = 0.0;
-        'outer: for line in lines {
-            for idx in line.indices {
-                let glyph = data.glyphs[idx];
-                let metrics = data.metrics[idx];
-
-                match glyph.character {
-                    '\n' => {
-                        continue 'outer;
-                    }
-                    '\r' => {
-                        calc_x = 0.0;
-                        calc_y -= vertical_shift;
-                        continue 'outer;
-                    }
-                    _ => {}
+            for line in &lines {
+                let rect = grow(line.rect, if self.has_outline() { outline } else { 0.0 });
+                let rect = BoundingBox::new(
+                    rect.x + offset.x,
+                    rect.y + offset.y,
+                    rect.width,
+                    rect.height,
+                );
+                draw_rect(rect, fade(shadow.color, line.opacity), batch);
+            }
+        }
+
+        if self.has_outline() {
+            self.draw_layer(&layout, &data, count, Vec2::ZERO, batch);
+
+            for line in &lines {
+                draw_rect(
+                    grow(line.rect, outline),
+                    fade(self.outline.color, line.opacity),
+                    batch,
+                );
+            }
+        }
+
+        // Underlines go beneath the glyphs they cross, and strikethroughs over them
+        for line in lines.iter().filter(|line| !line.strikethrough) {
+            draw_rect(line.rect, fade(line.color, line.opacity), batch);
+        }
+        self.draw_layer(&layout, &data, 0, Vec2::ZERO, batch);
+        for line in lines.iter().filter(|line| line.strikethrough) {
+            draw_rect(line.rect, fade(line.color, line.opacity), batch);
+        }
+    }
+}
+
+impl Text {
+    pub fn from_markup(
+        x: f32,
+        y: f32,
+        markup: &str,
+        font: Asset<Font>,
+    ) -> Result<Self, MarkupError> {
+        let (text, spans) = markup::parse(markup)?;
+        Ok(Self::new(x, y, text, font).with_spans(spans))
+    }
+
+    pub fn with_span(mut self, range: Range<usize>, style: TextStyle) -> Self {
+        self.spans.push(TextSpan { range, style });
+        self
+    }
+
+    fn visible(&self) -> bool {
+        self.color != Color::CLEAR
+            || self
+                .spans
+                .iter()
+                .any(|span| span.style.color.is_some_and(|color| color != Color::CLEAR))
+            || self.has_outline()
+            || self.visible_shadow().is_some()
+    }
+
+    fn has_outline(&self) -> bool {
+        self.outline.thickness > 0.0 && self.outline.color != Color::CLEAR
+    }
+
+    fn visible_shadow(&self) -> Option<Shadow> {
+        self.shadow.filter(|shadow| shadow.color != Color::CLEAR)
+    }
+
+    // Glyphs in each layer, being every character, maybe an ellipsis, and a hyphen for every
+    // soft hyphen
+    fn glyph_count(&self) -> usize {
+        let hyphens = self.text.chars().filter(|&c| c == SOFT_HYPHEN).count();
+        self.ellipsis() + (self.overflow == TextOverflow::Ellipsis) as usize + hyphens
+    }
+
+    // Sits right after the text's own glyphs, whether or not it's requested
+    fn ellipsis(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    // Hyphen drawn in place of the soft hyphen at index when a line breaks after it
+    fn hyphen(&self, index: usize) -> usize {
+        let before = self
+            .text
+            .chars()
+            .take(index)
+            .filter(|&c| c == SOFT_HYPHEN)
+            .count();
+        self.ellipsis() + (self.overflow == TextOverflow::Ellipsis) as usize + before
+    }
+
+    fn glyphs(&self, fonts: &FontAsset, properties: &Properties) -> Vec<Glyph> {
+        let fallbacks: Vec<_> = self.fallbacks.iter().map(|font| font.id).collect();
+
+        let mut characters: Vec<_> = self.text.chars().enumerate().collect();
+
+        // Always requested so truncation can swap it in, and sits after the text's own glyphs
+        if self.overflow == TextOverflow::Ellipsis {
+            characters.push((characters.len(), '…'));
+        }
+
+        // Styled like the soft hyphens they stand in for
+        let hyphens: Vec<_> = self
+            .text
+            .chars()
+            .enumerate()
+            .filter(|&(_, character)| character == SOFT_HYPHEN)
+            .map(|(idx, _)| (idx, '-'))
+            .collect();
+        characters.extend(hyphens);
+
+        characters
+            .into_iter()
+            .map(|(idx, character)| {
+                let mut style = TextStyle::default();
+                for span in self.spans.iter().filter(|span| span.range.contains(&idx)) {
+                    style = style.merge(&span.style);
                 }
 
-                let x = self.x + calc_x + metrics.xmin as f32;
-                let y = self.y + size + calc_y - metrics.ymin as f32;
+                let font = style.font.unwrap_or(self.font).id;
+                let thickness = style.thickness.unwrap_or(self.thickness);
+                let emphasis = style.emphasis.unwrap_or(self.emphasis);
+                let size = style.size.unwrap_or(self.size) * properties.scale_factor;
 
-                let width = metrics.width as f32;
-                let height = metrics.height as f32;
+                let lines = fonts.decorations(font, thickness, emphasis);
+                let scaled = |(offset, thickness): (f32, f32)| {
+                    (offset * size, f32::max(thickness * size, 1.0))
+                };
+                let decorations = Decorations {
+                    underline: style
+                        .underline
+                        .unwrap_or(self.underline)
+                        .then(|| scaled(lines.underline)),
+                    strikethrough: style
+                        .strikethrough
+                        .unwrap_or(self.strikethrough)
+                        .then(|| scaled(lines.strikeout)),
+                };
 
-                let x_offset = match self.align {
-                    Align::Left => 0.0,
-                    Align::Center => -0.5 * line.width,
-                    Align::Right => total_width - line.width,
+                let mut chain = vec![font];
+                if font != self.font.id {
+                    chain.push(self.font.id);
+                }
+                chain.extend(&fallbacks);
+
+                let font_id = fonts.resolve(character, &chain, thickness, emphasis);
+                Glyph {
+                    character,
+                    font_id,
+                    image_id: u32::MAX,
+                    size,
+                    color: style.color.unwrap_or(self.color),
+                    thickness,
+                    emphasis,
+                    stretch: self.stretch,
+                    ascent: fonts.ascent(font_id, thickness, emphasis, size),
+                    decorations,
+                    outline: 0.0,
+                    blur: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn measure(&self, fonts: &FontAsset, properties: &Properties) -> TextMeasurement {
+        let layout = self.arrange(fonts, properties);
+
+        TextMeasurement {
+            width: layout.width,
+            height: layout.height,
+            line_count: layout.lines.len(),
+            lines: layout
+                .lines
+                .iter()
+                .map(|line| layout.to_screen(line.bounds))
+                .collect(),
+        }
+    }
+
+    // Layout without rasterizing anything, for use outside of rendering
+    pub(crate) fn arrange(&self, fonts: &FontAsset, properties: &Properties) -> TextLayout {
+        let glyphs = self.glyphs(fonts, properties);
+        let metrics = glyphs.iter().map(|glyph| fonts.measure(glyph)).collect();
+
+        self.layout(
+            &TextRenderingData { glyphs, metrics },
+            properties.scale_factor,
+        )
+    }
+
+    // Lays out along the lines, then turns the result for vertical text
+    pub(crate) fn layout(&self, data: &TextRenderingData, scale: f32) -> TextLayout {
+        if self.writing_mode == WritingMode::Horizontal {
+            let mut layout = self.flow(data, scale);
+            for placed in &mut layout.glyphs {
+                placed.origin = Vec2::new(placed.x, placed.baseline);
+            }
+            return layout;
+        }
+
+        // Glyphs advance by their vertical metrics, or a full em when the font has none
+        let mut vertical = data.clone();
+        for (metrics, glyph) in vertical.metrics.iter_mut().zip(&data.glyphs) {
+            if metrics.advance_width != 0.0 {
+                metrics.advance_width = if metrics.advance_height > 0.0 {
+                    metrics.advance_height
+                } else {
+                    glyph.size
                 };
+            }
+        }
 
-                self.draw_char(x + x_offset, y, width, height, glyph.image_id, batch);
+        let mut layout = self.flow(&vertical, scale);
+        layout.writing_mode = self.writing_mode;
 
-                calc_x += metrics.advance_width;
+        // Upright glyphs are centered in their column, hanging from the top of their em box
+        for line in &layout.lines {
+            let column = layout.to_screen(line.bounds);
+            for placed in &mut layout.glyphs[line.glyphs.clone()] {
+                let glyph = data.glyphs[placed.index];
+                let advance = data.metrics[placed.index].advance_width;
+                let top = self.y + placed.x - self.x;
+
+                placed.origin = Vec2::new(
+                    column.x + 0.5 * (column.width - advance),
+                    top + glyph.ascent,
+                );
             }
+        }
+
+        layout.bounds = layout.to_screen(layout.bounds);
+        (layout.width, layout.height) = (layout.height, layout.width);
+
+        layout
+    }
+
+    // Places every visible glyph along its line, keeping the pen position and baseline of each
+    fn flow(&self, data: &TextRenderingData, scale: f32) -> TextLayout {
+        let max_width = self.width.unwrap_or(f32::INFINITY);
+        let mut lines = self.lines(data, max_width);
+
+        let truncated = self.truncate(&mut lines, data, scale);
+
+        let used_width = lines
+            .iter()
+            .fold(0.0, |acc, line| f32::max(acc, line.width));
+        let total_width = self.width.unwrap_or(used_width);
+
+        let length = self.text.chars().count();
+
+        let mut glyphs = Vec::new();
+        let mut line_layouts = Vec::new();
+        let mut calc_y = 0.0;
+        let mut last_shift = self.size * scale * self.line_height;
+        'outer: for (i, line) in lines.iter().enumerate() {
+            let size = self.line_size(line, data, scale);
+            let vertical_shift = size * self.line_height;
+
+            // Trailing whitespace neither counts towards justification nor receives any gap
+            let content = line
+                .indices
+                .iter()
+                .rposition(|&idx| !data.glyphs[idx].character.is_whitespace())
+                .map_or(0, |last| last + 1);
+            let content_width: f32 = line.advances[..content].iter().sum();
+            let gaps = line.indices[..content]
+                .iter()
+                .filter(|&&idx| data.glyphs[idx].character == ' ')
+                .count();
+
+            // The last line of a paragraph is left aligned instead of stretched
+            let justified = self.align == Align::Justify
+                && gaps > 0
+                && total_width.is_finite()
+                && lines.get(i + 1).is_some_and(|next| !next.is_break(data));
+            let gap = if justified {
+                (total_width - content_width) / gaps as f32
+            } else {
+                0.0
+            };
+
+            let x_offset = match self.align {
+                Align::Left | Align::Justify => 0.0,
+                Align::Center => -0.5 * line.width,
+                Align::Right => total_width - line.width,
+            };
+
+            let start = glyphs.len();
+            let mut calc_x = 0.0;
+            for (position, (&idx, &advance)) in line.indices.iter().zip(&line.advances).enumerate()
+            {
+                let character = data.glyphs[idx].character;
+                if character == '\r' {
+                    calc_y -= last_shift;
+                    continue 'outer;
+                } else if is_newline(character) {
+                    continue 'outer;
+                }
+
+                glyphs.push(PlacedGlyph {
+                    index: idx,
+                    x: self.x + x_offset + calc_x,
+                    baseline: self.y + size + calc_y,
+                    advance,
+                    opacity: 1.0,
+                    origin: Vec2::ZERO,
+                });
+
+                calc_x += advance;
+                if character == ' ' && position < content {
+                    calc_x += gap;
+                }
+            }
+
+            // A caret at a soft wrap sits at the start of the next line, or before the space
+            // hanging off the end of this one
+            let end = match lines.get(i + 1) {
+                Some(next) if !next.is_break(data) => {
+                    let hanging = next.start > line.start
+                        && data.glyphs[next.start - 1].character.is_whitespace();
+                    next.start - hanging as usize
+                }
+                Some(next) => next.start,
+                None => length,
+            };
+
+            line_layouts.push(LineLayout {
+                glyphs: start..glyphs.len(),
+                chars: line.start..end,
+                bounds: BoundingBox::new(
+                    self.x + x_offset,
+                    self.y + calc_y,
+                    if justified { total_width } else { line.width },
+                    vertical_shift,
+                ),
+                baseline: self.y + size + calc_y,
+            });
 
-            calc_x = 0.0;
             calc_y += vertical_shift;
+            last_shift = vertical_shift;
         }
-    }
-}
 
-impl Text {
-    // Chunks of glyph and metrics indicies by line, alongside the bounding width
-    fn lines(&self, data: &TextRenderingData, max_width: f32, scale: f32) -> (Vec<Chunk>, f32) {
-        let mut glyph_groups = Vec::new();
-        let mut cur_group = Vec::new();
-        let mut was_ws = false;
-        for (i, c) in self.text.chars().enumerate() {
-            if was_ws && c == ' ' || c == '\t' {
-                continue;
+        // Fade the last visible line out over its final few ems
+        if truncated && self.overflow == TextOverflow::Fade {
+            if let Some(line) = line_layouts.last() {
+                let end = line.bounds.x + line.bounds.width;
+                let size = line.baseline - line.bounds.y;
+                let distance = f32::min(line.bounds.width, size * 3.0);
+
+                for placed in &mut glyphs[line.glyphs.clone()] {
+                    let center = placed.x + 0.5 * placed.advance;
+                    placed.opacity = ((end - center) / distance).clamp(0.0, 1.0);
+                }
             }
+        }
 
-            if c == ' ' || c == '\n' || c == '\r' || c == '\t' {
-                glyph_groups.push(cur_group.clone());
-                glyph_groups.push(vec![i]);
-                cur_group.clear();
-                was_ws = true;
-                continue;
+        let height = line_layouts.iter().fold(0.0, |acc, line| {
+            f32::max(acc, line.bounds.y + line.bounds.height - self.y)
+        });
+
+        let y_offset = match (self.vertical_align, self.max_height) {
+            (VerticalAlign::Top, _) => 0.0,
+            (VerticalAlign::Middle, Some(box_height)) => 0.5 * (box_height - height),
+            (VerticalAlign::Middle, None) => -0.5 * height,
+            (VerticalAlign::Bottom, Some(box_height)) => box_height - height,
+            (VerticalAlign::Bottom, None) => -height,
+            (VerticalAlign::Baseline, _) => line_layouts
+                .first()
+                .map_or(0.0, |line| self.y - line.baseline),
+        };
+
+        for placed in &mut glyphs {
+            placed.baseline += y_offset;
+        }
+        for line in &mut line_layouts {
+            line.bounds.y += y_offset;
+            line.baseline += y_offset;
+        }
+
+        let left = match self.align {
+            Align::Left | Align::Justify | Align::Right => self.x,
+            Align::Center => self.x - 0.5 * total_width,
+        };
+        let (top, box_height) = match self.max_height {
+            Some(box_height) => (self.y, box_height),
+            None => (self.y + y_offset, height),
+        };
+
+        TextLayout {
+            glyphs,
+            lines: line_layouts,
+            writing_mode: WritingMode::Horizontal,
+            origin: Vec2::new(self.x, self.y),
+            width: used_width,
+            height,
+            bounds: BoundingBox::new(left, top, total_width, box_height),
+        }
+    }
+
+    // Largest glyph on the line, so mixed sizes never overlap the line above
+    fn line_size(&self, line: &Chunk, data: &TextRenderingData, scale: f32) -> f32 {
+        line.indices
+            .iter()
+            .map(|&idx| data.glyphs[idx].size)
+            .reduce(f32::max)
+            .unwrap_or(self.size * scale)
+    }
+
+    // Drops the lines past max_lines or max_height, returning whether anything was cut
+    fn truncate(&self, lines: &mut Vec<Chunk>, data: &TextRenderingData, scale: f32) -> bool {
+        let is_break = |chunk: &Chunk| chunk.is_break(data);
+
+        let max_lines = self.max_lines.unwrap_or(usize::MAX);
+        let max_height = self.max_height.unwrap_or(f32::INFINITY);
+
+        let mut visible = 0;
+        let mut height = 0.0;
+        let cut = lines.iter().position(|line| {
+            if is_break(line) {
+                return false;
             }
 
-            cur_group.push(i);
-            was_ws = false;
+            visible += 1;
+            height += self.line_size(line, data, scale) * self.line_height;
+            visible > max_lines || height > max_height
+        });
+
+        let Some(cut) = cut else {
+            return false;
+        };
+
+        lines.truncate(cut);
+        while lines.last().is_some_and(is_break) {
+            lines.pop();
         }
-        if !cur_group.is_empty() {
-            glyph_groups.push(cur_group);
+
+        if self.overflow == TextOverflow::Ellipsis {
+            if let Some(last) = lines.last_mut() {
+                let ellipsis = self.ellipsis();
+                let ellipsis_width = data.metrics[ellipsis].advance_width;
+                let available = self.width.unwrap_or(f32::INFINITY);
+
+                while let Some(&idx) = last.indices.last() {
+                    if last.width + ellipsis_width <= available
+                        && !data.glyphs[idx].character.is_whitespace()
+                    {
+                        break;
+                    }
+
+                    last.pop();
+                }
+
+                last.add(ellipsis, ellipsis_width);
+            }
         }
 
+        true
+    }
+
+    // Chunks of glyph indices by line, broken at UAX #14 opportunities and never inside a
+    // grapheme cluster
+    fn lines(&self, data: &TextRenderingData, max_width: f32) -> Vec<Chunk> {
+        let offsets: Vec<_> = self.text.char_indices().map(|(offset, _)| offset).collect();
+        let to_index = |offset: usize| offsets.partition_point(|&o| o < offset);
+
+        let clusters: Vec<_> = self
+            .text
+            .grapheme_indices(true)
+            .map(|(offset, grapheme)| {
+                let start = to_index(offset);
+                start..start + grapheme.chars().count()
+            })
+            .collect();
+
         let mut lines = Vec::new();
-        let mut cur_line = Chunk::empty();
-        let mut width = 0.0;
-        'outer: for group in glyph_groups {
-            let mut breaks = Vec::new();
-            let mut cur_break = Chunk::empty();
-            for idx in group {
-                let character = data.glyphs[idx].character;
-                let metrics = data.metrics[idx];
+        let mut line = Chunk::empty(0);
+        let mut cluster = 0;
+        for (offset, _) in linebreaks(&self.text) {
+            let end = to_index(offset);
+            let first = cluster;
+            while cluster < clusters.len() && clusters[cluster].start < end {
+                cluster += 1;
+            }
 
-                if character == '\n' || character == '\r' {
-                    lines.push(cur_line.clone());
-                    lines.push(Chunk::new(vec![idx], 0.0));
-                    cur_line.reset();
-                    continue 'outer;
+            self.segment(
+                &clusters[first..cluster],
+                data,
+                max_width,
+                &mut line,
+                &mut lines,
+            );
+        }
+        // Even empty text, or text ending in a newline, has a last line for a caret to sit on
+        if !line.is_empty() || lines.last().is_none_or(|last| last.is_break(data)) {
+            lines.push(line);
+        }
+
+        // A soft hyphen ending a wrapped line shows, so its hyphen takes its place
+        for i in 1..lines.len() {
+            if lines[i].is_break(data) {
+                continue;
+            }
+
+            let line = &mut lines[i - 1];
+            if let Some(&idx) = line.indices.last() {
+                if data.glyphs[idx].character == SOFT_HYPHEN {
+                    let hyphen = self.hyphen(idx);
+                    line.pop();
+                    line.add(hyphen, self.advance(hyphen, data, line.width));
                 }
+            }
+        }
 
-                let line_width = cur_line.width + cur_break.width;
+        lines
+    }
 
-                if line_width + metrics.width as f32 > max_width {
-                    breaks.push(cur_break.clone());
-                    cur_break = Chunk::new(vec![idx], metrics.advance_width);
-                } else {
-                    cur_break.add(idx, metrics.advance_width);
+    // Places the clusters between two break opportunities, which end in whitespace and possibly
+    // a mandatory break
+    fn segment(
+        &self,
+        clusters: &[Range<usize>],
+        data: &TextRenderingData,
+        max_width: f32,
+        line: &mut Chunk,
+        lines: &mut Vec<Chunk>,
+    ) {
+        let character = |idx: usize| data.glyphs[idx].character;
+        let is_space = |cluster: &Range<usize>| {
+            cluster
+                .clone()
+                .all(|idx| character(idx).is_whitespace() && !is_newline(character(idx)))
+        };
 
-                    if line_width + metrics.advance_width > max_width {
-                        breaks.push(cur_break.clone());
-                        cur_break.reset();
-                    }
-                }
+        let (clusters, newline) = match clusters.split_last() {
+            Some((last, rest)) if last.clone().any(|idx| is_newline(character(idx))) => {
+                (rest, Some(last))
+            }
+            _ => (clusters, None),
+        };
+
+        let content = clusters
+            .iter()
+            .rposition(|cluster| !is_space(cluster))
+            .map_or(0, |last| last + 1);
+        let (words, spaces) = clusters.split_at(content);
+
+        // The line may break after a soft hyphen ending the words, so it only fits if the hyphen
+        // drawn there does too
+        let width = |cluster: &Range<usize>, x: f32| {
+            if Some(cluster) == words.last() && character(cluster.start) == SOFT_HYPHEN {
+                return self.advance(self.hyphen(cluster.start), data, x);
+            }
+
+            cluster
+                .clone()
+                .fold(0.0, |acc, idx| acc + self.advance(idx, data, x + acc))
+        };
+
+        // Words move to the next line whole, unless they don't fit on a line of their own either
+        if let (OverflowBreak::Word, Some(first)) = (self.overflow_break, words.first()) {
+            let total = words
+                .iter()
+                .fold(0.0, |acc, cluster| acc + width(cluster, line.width + acc));
+
+            if !line.is_empty() && line.width + total > max_width {
+                lines.push(mem::replace(line, Chunk::empty(first.start)));
+            }
+        }
+
+        for cluster in words {
+            let width = width(cluster, line.width);
+
+            if !line.is_empty() && line.width + width > max_width {
+                lines.push(mem::replace(line, Chunk::empty(cluster.start)));
+            }
+
+            for idx in cluster.clone() {
+                line.add(idx, self.advance(idx, data, line.width));
             }
-            if !cur_break.is_empty() {
-                breaks.push(cur_break);
+        }
+
+        // Trailing whitespace hangs past the edge instead of starting a line
+        for cluster in spaces {
+            let collapsible = self.trim_whitespace
+                && character(cluster.start) == ' '
+                && line
+                    .indices
+                    .last()
+                    .is_none_or(|&idx| character(idx).is_whitespace());
+            if collapsible {
+                continue;
             }
 
-            let glyph_chunk = Chunk::flatten(&breaks);
-            if glyph_chunk.width > width {
-                width = glyph_chunk.width;
+            for idx in cluster.clone() {
+                line.add(idx, self.advance(idx, data, line.width));
             }
+        }
+
+        // "\r\n" is one cluster, which breaks once rather than returning and then breaking
+        if let Some(newline) = newline {
+            lines.push(mem::replace(line, Chunk::empty(newline.end)));
+            lines.push(Chunk::marker(newline.start, newline.end - 1));
+        }
+    }
+
+    // Tabs advance to the next stop, every tab_size spaces from the start of the line
+    fn advance(&self, idx: usize, data: &TextRenderingData, x: f32) -> f32 {
+        let advance = data.metrics[idx].advance_width;
+        if data.glyphs[idx].character != '\t' {
+            return advance;
+        }
+
+        let stop = self.tab_size * advance;
+        if stop <= 0.0 {
+            return 0.0;
+        }
+
+        (f32::floor(x / stop) + 1.0) * stop - x
+    }
+
+    // Runs of underline and strikethrough, merged across glyphs that share the same line
+    fn decorations(&self, layout: &TextLayout, data: &TextRenderingData) -> Vec<DecorationLine> {
+        let mut runs: Vec<DecorationLine> = Vec::new();
 
-            if glyph_chunk.width > max_width {
-                for chunk in breaks {
-                    cur_line.extend(&chunk);
-                    lines.push(cur_line.clone());
-                    cur_line.reset();
+        for line in &layout.lines {
+            let placed = &layout.glyphs[line.glyphs.clone()];
+            let first = runs.len();
+
+            // Whitespace hanging off the end of a line isn't decorated
+            let content = placed
+                .iter()
+                .rposition(|placed| !data.glyphs[placed.index].character.is_whitespace())
+                .map_or(0, |last| last + 1);
+
+            for (i, current) in placed[..content].iter().enumerate() {
+                let glyph = data.glyphs[current.index];
+                let end = placed
+                    .get(i + 1)
+                    .map_or(current.x + current.advance, |next| next.x);
+
+                let kinds = [
+                    (glyph.decorations.underline, false),
+                    (glyph.decorations.strikethrough, true),
+                ];
+                for (metrics, strikethrough) in kinds {
+                    let Some((offset, thickness)) = metrics else {
+                        continue;
+                    };
+
+                    let run = DecorationLine {
+                        rect: BoundingBox::new(
+                            current.x,
+                            current.baseline - offset,
+                            end - current.x,
+                            thickness,
+                        ),
+                        color: glyph.color,
+                        opacity: current.opacity,
+                        strikethrough,
+                    };
+
+                    let previous = runs[first..]
+                        .iter_mut()
+                        .rev()
+                        .find(|previous| previous.strikethrough == strikethrough);
+                    match previous {
+                        Some(previous) if previous.continues(&run) => {
+                            previous.rect.width = end - previous.rect.x;
+                        }
+                        _ => runs.push(run),
+                    }
                 }
-            } else if cur_line.width + glyph_chunk.width > max_width {
-                lines.push(cur_line.clone());
-                cur_line.reset();
-                cur_line.extend(&glyph_chunk);
-            } else {
-                cur_line.extend(&glyph_chunk);
             }
         }
-        if !cur_line.is_empty() {
-            lines.push(cur_line);
+
+        for run in &mut runs {
+            run.rect = layout.to_screen(run.rect);
         }
 
-        (lines, width * scale)
+        runs
     }
 
-    fn draw_char(
+    fn draw_layer(
         &self,
-        x: f32,
-        y: f32,
-        width: f32,
-        height: f32,
-        image_id: u32,
+        layout: &TextLayout,
+        data: &TextRenderingData,
+        layer: usize,
+        offset: Vec2,
         batch: &mut RenderBatch,
     ) {
+        for placed in &layout.glyphs {
+            let glyph = data.glyphs[layer + placed.index];
+            let metrics = data.metrics[layer + placed.index];
+
+            let (width, height) = (metrics.width as f32, metrics.height as f32);
+            let rect = BoundingBox::new(
+                placed.origin.x + offset.x + metrics.xmin as f32,
+                placed.origin.y + offset.y - metrics.ymin as f32 - height,
+                width,
+                height,
+            );
+            self.draw_char(rect, glyph.image_id, placed.opacity, batch);
+        }
+    }
+
+    fn draw_char(&self, rect: BoundingBox, image_id: u32, opacity: f32, batch: &mut RenderBatch) {
         let image = batch.assets.fonts.atlas.get(image_id).clone();
+        let tint = Color::new(1.0, 1.0, 1.0, opacity);
+        let (left, top) = (rect.x, rect.y);
+        let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
 
         batch.triangle(
-            Vertex::new(x, y, image.u, image.v + image.height, Color::CLEAR, 1),
+            Vertex::new(left, bottom, image.u, image.v + image.height, tint, 1),
             Vertex::new(
-                x + width,
-                y,
+                right,
+                bottom,
                 image.u + image.width,
                 image.v + image.height,
-                Color::CLEAR,
+                tint,
                 1,
             ),
-            Vertex::new(x, y - height, image.u, image.v, Color::CLEAR, 1),
+            Vertex::new(left, top, image.u, image.v, tint, 1),
         );
 
         batch.triangle(
             Vertex::new(
-                x + width,
-                y,
+                right,
+                bottom,
                 image.u + image.width,
                 image.v + image.height,
-                Color::CLEAR,
-                1,
-            ),
-            Vertex::new(x, y - height, image.u, image.v, Color::CLEAR, 1),
-            Vertex::new(
-                x + width,
-                y - height,
-                image.u + image.width,
-                image.v,
-                Color::CLEAR,
+                tint,
                 1,
             ),
+            Vertex::new(left, top, image.u, image.v, tint, 1),
+            Vertex::new(right, top, image.u + image.width, image.v, tint, 1),
         );
     }
 }
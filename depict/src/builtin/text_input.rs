@@ -0,0 +1,616 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use winit::{
+    dpi::PhysicalSize,
+    event::{Ime, MouseButton},
+    keyboard::{Key, NamedKey},
+};
+
+use crate::{
+    component::bounds::BoundingBox,
+    core::{
+        context::Context,
+        properties::{Background, WritingMode},
+    },
+    graphics::color::Color,
+};
+
+use super::{
+    rectangle::Rectangle,
+    text::{Text, TextLayout},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Replace,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    value: String,
+    caret: usize,
+    anchor: usize,
+}
+
+// Uncommitted IME text, shown at the caret until it's committed or cancelled
+#[derive(Debug, Clone)]
+struct Preedit {
+    text: String,
+    cursor: Option<usize>,
+}
+
+// Editable text, which keeps its state between frames unlike shapes.
+// Call update in the update step and render in the render step.
+#[derive(Debug, Clone)]
+pub struct TextInput {
+    style: Text,
+    value: String,
+    // Character indices, with the selection between anchor and caret
+    caret: usize,
+    anchor: usize,
+    focused: bool,
+    multiline: bool,
+    dragging: bool,
+    preedit: Option<Preedit>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+    blink_start: f64,
+    caret_color: Color,
+    selection_color: Color,
+    // Of the shown text as of the last update, for render to reuse
+    layout: Option<TextLayout>,
+}
+
+impl TextInput {
+    const HISTORY: usize = 100;
+    const BLINK: f64 = 0.5;
+
+    // Everything but the text itself (position, font, size, width, ...) comes from style
+    pub fn new(style: Text) -> Self {
+        Self {
+            style,
+            value: String::new(),
+            caret: 0,
+            anchor: 0,
+            focused: false,
+            multiline: false,
+            dragging: false,
+            preedit: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            blink_start: 0.0,
+            caret_color: Color::BLACK,
+            selection_color: Color::new(0.2, 0.4, 1.0, 0.4),
+            layout: None,
+        }
+    }
+
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    pub fn with_multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    pub fn with_caret_color(mut self, caret_color: Color) -> Self {
+        self.caret_color = caret_color;
+        self
+    }
+
+    pub fn with_selection_color(mut self, selection_color: Color) -> Self {
+        self.selection_color = selection_color;
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    // Replaces the value without an undo step
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_string();
+        self.caret = self.length();
+        self.anchor = self.caret;
+        self.preedit = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+        self.layout = None;
+    }
+
+    // Restyles or moves the input
+    pub fn set_style(&mut self, style: Text) {
+        self.style = style;
+        self.layout = None;
+    }
+
+    pub fn selection(&self) -> Range<usize> {
+        usize::min(self.caret, self.anchor)..usize::max(self.caret, self.anchor)
+    }
+
+    pub fn selected(&self) -> &str {
+        let range = self.selection();
+        &self.value[self.byte(range.start)..self.byte(range.end)]
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn focus(&mut self, ctx: &mut Context) {
+        if !self.focused {
+            self.focused = true;
+            self.blink_start = ctx.time.seconds();
+            ctx.window.set_ime_allowed(true);
+        }
+    }
+
+    pub fn blur(&mut self, ctx: &mut Context) {
+        if self.focused {
+            self.focused = false;
+            self.dragging = false;
+            self.preedit = None;
+            self.layout = None;
+            ctx.window.set_ime_allowed(false);
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut Context) {
+        let shown = self.shown();
+        let mut layout = ctx.layout_text(&self.text());
+
+        self.pointer(ctx, &layout);
+        if self.focused {
+            self.keyboard(ctx, &layout);
+        }
+
+        // Only edits and compositions change what's laid out
+        if self.shown() != shown {
+            layout = ctx.layout_text(&self.text());
+        }
+
+        // Keeps the IME candidate window next to the caret
+        if self.focused {
+            let caret = layout.caret(self.display_caret());
+            ctx.window.set_ime_cursor_area(
+                ctx.to_window(caret.x, caret.y),
+                PhysicalSize::new(f32::max(caret.width, 1.0), f32::max(caret.height, 1.0)),
+            );
+        }
+
+        self.layout = Some(layout);
+    }
+
+    fn keyboard(&mut self, ctx: &mut Context, layout: &TextLayout) {
+        let (caret, anchor, value) = (self.caret, self.anchor, self.value.clone());
+
+        for ime in ctx.keyboard.ime().to_vec() {
+            self.ime(ime);
+        }
+
+        for event in ctx.keyboard.presses().to_vec() {
+            let modifiers = ctx.keyboard.modifiers();
+            let shift = modifiers.shift_key();
+            // AltGr comes through as ctrl+alt on Windows, and types rather than runs shortcuts
+            let command =
+                (modifiers.control_key() && !modifiers.alt_key()) || modifiers.super_key();
+            let word = modifiers.control_key() || modifiers.alt_key();
+
+            // Composition owns the keyboard until it finishes
+            if self.preedit.is_some() {
+                continue;
+            }
+
+            let key = match &event.logical_key {
+                Key::Named(named) => Key::Named(Self::arrow(*named, layout.writing_mode())),
+                key => key.clone(),
+            };
+
+            match &key {
+                Key::Named(NamedKey::ArrowLeft) => {
+                    let target = if self.has_selection() && !shift {
+                        self.selection().start
+                    } else {
+                        self.previous(self.caret, word)
+                    };
+                    self.select_to(target, shift);
+                }
+                Key::Named(NamedKey::ArrowRight) => {
+                    let target = if self.has_selection() && !shift {
+                        self.selection().end
+                    } else {
+                        self.next(self.caret, word)
+                    };
+                    self.select_to(target, shift);
+                }
+                Key::Named(key @ (NamedKey::ArrowUp | NamedKey::ArrowDown)) => {
+                    let forward = *key == NamedKey::ArrowDown;
+                    let target = self.clamp(layout.adjacent_line(self.caret, forward));
+                    self.select_to(target, shift);
+                }
+                Key::Named(NamedKey::Home) => {
+                    let target = if command {
+                        0
+                    } else {
+                        layout.line_chars(self.caret).start
+                    };
+                    self.select_to(self.clamp(target), shift);
+                }
+                Key::Named(NamedKey::End) => {
+                    let target = if command {
+                        self.length()
+                    } else {
+                        layout.line_chars(self.caret).end
+                    };
+                    self.select_to(self.clamp(target), shift);
+                }
+                Key::Named(NamedKey::Backspace) => {
+                    if !self.has_selection() {
+                        self.anchor = self.previous(self.caret, word);
+                    }
+                    self.edit(EditKind::Delete, "");
+                }
+                Key::Named(NamedKey::Delete) => {
+                    if !self.has_selection() {
+                        self.anchor = self.next(self.caret, word);
+                    }
+                    self.edit(EditKind::Delete, "");
+                }
+                Key::Named(NamedKey::Enter) if self.multiline => {
+                    self.edit(EditKind::Insert, "\n");
+                }
+                Key::Named(NamedKey::Escape) => {
+                    self.anchor = self.caret;
+                }
+                Key::Character(character) if command => match character.to_lowercase().as_str() {
+                    "a" => {
+                        self.anchor = 0;
+                        self.caret = self.length();
+                    }
+                    "c" if self.has_selection() => {
+                        ctx.set_clipboard(self.selected());
+                    }
+                    "x" if self.has_selection() => {
+                        ctx.set_clipboard(self.selected());
+                        self.edit(EditKind::Replace, "");
+                    }
+                    "v" => {
+                        if let Some(pasted) = ctx.clipboard() {
+                            self.edit(EditKind::Replace, &self.filter(&pasted));
+                        }
+                    }
+                    "z" if shift => self.redo(),
+                    "z" => self.undo(),
+                    "y" => self.redo(),
+                    _ => {}
+                },
+                _ => {
+                    if let Some(text) = &event.text {
+                        let text = self.filter(text);
+                        if !text.is_empty() && !command {
+                            self.edit(EditKind::Insert, &text);
+                        }
+                    }
+                }
+            }
+        }
+
+        if (caret, anchor) != (self.caret, self.anchor) || value != self.value {
+            self.blink_start = ctx.time.seconds();
+        }
+    }
+
+    pub fn render(&self, ctx: &mut Context) {
+        let text = self.text();
+        let laid_out;
+        let layout = match &self.layout {
+            Some(layout) => layout,
+            None => {
+                laid_out = ctx.layout_text(&text);
+                &laid_out
+            }
+        };
+
+        if self.focused {
+            ctx.draw_all(
+                layout.highlights(self.selection(), Background::Color(self.selection_color)),
+            );
+        }
+
+        ctx.draw(text);
+
+        if !self.focused {
+            return;
+        }
+
+        let thickness = f32::max(ctx.properties.scale_factor, 1.0);
+
+        // Underlines the composition, as most platforms do
+        if let Some(preedit) = &self.preedit {
+            let start = self.caret;
+            let end = start + preedit.text.chars().count();
+            ctx.draw_all(
+                layout
+                    .selection(start..end)
+                    .into_iter()
+                    .map(|rect| {
+                        let line = match layout.writing_mode() {
+                            WritingMode::Horizontal => Rectangle::new(
+                                rect.x,
+                                rect.y + rect.height - thickness,
+                                rect.width,
+                                thickness,
+                            ),
+                            _ => Rectangle::new(
+                                rect.x + rect.width - thickness,
+                                rect.y,
+                                thickness,
+                                rect.height,
+                            ),
+                        };
+                        line.with_background(Background::Color(self.caret_color))
+                    })
+                    .collect(),
+            );
+        }
+
+        let elapsed = ctx.time.seconds() - self.blink_start;
+        if ((elapsed / Self::BLINK) as u64).is_multiple_of(2) {
+            let caret = layout.caret(self.display_caret());
+            ctx.draw(
+                Rectangle::new(
+                    caret.x,
+                    caret.y,
+                    f32::max(caret.width, thickness),
+                    f32::max(caret.height, thickness),
+                )
+                .with_background(Background::Color(self.caret_color)),
+            );
+        }
+    }
+
+    // Arrow keys as directions along (left and right) and across (up and down) the lines, which
+    // turn with vertical text
+    fn arrow(key: NamedKey, writing_mode: WritingMode) -> NamedKey {
+        match (writing_mode, key) {
+            (WritingMode::Horizontal, key) => key,
+            (_, NamedKey::ArrowUp) => NamedKey::ArrowLeft,
+            (_, NamedKey::ArrowDown) => NamedKey::ArrowRight,
+            (WritingMode::VerticalRl, NamedKey::ArrowRight)
+            | (WritingMode::VerticalLr, NamedKey::ArrowLeft) => NamedKey::ArrowUp,
+            (WritingMode::VerticalRl, NamedKey::ArrowLeft)
+            | (WritingMode::VerticalLr, NamedKey::ArrowRight) => NamedKey::ArrowDown,
+            (_, key) => key,
+        }
+    }
+
+    fn pointer(&mut self, ctx: &mut Context, layout: &TextLayout) {
+        let pressed = ctx.mouse.pressed() && ctx.mouse.button == MouseButton::Left;
+        let just_pressed = pressed && !ctx.mouse.last.pressed();
+        let (x, y) = (ctx.mouse.pos.x, ctx.mouse.pos.y);
+
+        if !pressed {
+            self.dragging = false;
+            return;
+        }
+
+        if just_pressed {
+            // An empty input without a width lays out to nothing across, so the caret's box keeps
+            // it clickable
+            let thickness = f32::max(ctx.properties.scale_factor, 1.0);
+            let caret = layout.caret(self.display_caret());
+            let hit = layout.bounds.contains(x, y)
+                || BoundingBox::new(
+                    caret.x,
+                    caret.y,
+                    f32::max(caret.width, thickness),
+                    f32::max(caret.height, thickness),
+                )
+                .contains(x, y);
+            if !hit {
+                self.blur(ctx);
+                return;
+            }
+
+            self.focus(ctx);
+            self.dragging = true;
+            self.preedit = None;
+
+            let shift = ctx.keyboard.modifiers().shift_key();
+            self.select_to(self.clamp(layout.index_at(x, y)), shift);
+            self.blink_start = ctx.time.seconds();
+        } else if self.dragging {
+            self.select_to(self.clamp(layout.index_at(x, y)), true);
+        }
+    }
+
+    fn ime(&mut self, ime: Ime) {
+        match ime {
+            Ime::Preedit(text, cursor) => {
+                self.preedit = (!text.is_empty()).then(|| Preedit {
+                    cursor: cursor.map(|(start, _)| text[..start].chars().count()),
+                    text,
+                });
+            }
+            Ime::Commit(text) => {
+                self.preedit = None;
+                let text = self.filter(&text);
+                self.edit(EditKind::Insert, &text);
+            }
+            Ime::Disabled => {
+                self.preedit = None;
+            }
+            Ime::Enabled => {}
+        }
+    }
+
+    // The value with any composition spliced in at the caret
+    fn shown(&self) -> String {
+        let mut value = self.value.clone();
+        if let Some(preedit) = &self.preedit {
+            value.insert_str(self.byte(self.caret), &preedit.text);
+        }
+
+        value
+    }
+
+    fn text(&self) -> Text {
+        self.style.clone().with_text(self.shown())
+    }
+
+    fn display_caret(&self) -> usize {
+        match &self.preedit {
+            Some(preedit) => {
+                self.caret
+                    + preedit
+                        .cursor
+                        .unwrap_or_else(|| preedit.text.chars().count())
+            }
+            None => self.caret,
+        }
+    }
+
+    // Replaces the selection, merging runs of the same kind of edit into one undo step
+    fn edit(&mut self, kind: EditKind, text: &str) {
+        let range = self.selection();
+        if range.is_empty() && text.is_empty() {
+            return;
+        }
+
+        if self.last_edit != Some(kind) || kind == EditKind::Replace {
+            self.undo.push(self.snapshot());
+            if self.undo.len() > Self::HISTORY {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.last_edit = Some(kind);
+
+        let (start, end) = (self.byte(range.start), self.byte(range.end));
+        self.value.replace_range(start..end, text);
+
+        self.caret = range.start + text.chars().count();
+        self.anchor = self.caret;
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            self.redo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            value: self.value.clone(),
+            caret: self.caret,
+            anchor: self.anchor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.value = snapshot.value;
+        self.caret = snapshot.caret;
+        self.anchor = snapshot.anchor;
+        self.last_edit = None;
+    }
+
+    fn select_to(&mut self, index: usize, extend: bool) {
+        self.caret = index;
+        if !extend {
+            self.anchor = index;
+        }
+        self.last_edit = None;
+    }
+
+    fn has_selection(&self) -> bool {
+        self.caret != self.anchor
+    }
+
+    // Single line inputs turn pasted newlines into spaces, and control characters are dropped
+    fn filter(&self, text: &str) -> String {
+        text.chars()
+            .filter_map(|c| match c {
+                '\n' if !self.multiline => Some(' '),
+                '\n' | '\t' => Some(c),
+                c if c.is_control() => None,
+                c => Some(c),
+            })
+            .collect()
+    }
+
+    fn length(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn clamp(&self, index: usize) -> usize {
+        usize::min(index, self.length())
+    }
+
+    fn byte(&self, index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(index)
+            .map_or(self.value.len(), |(byte, _)| byte)
+    }
+
+    fn index(&self, byte: usize) -> usize {
+        self.value[..byte].chars().count()
+    }
+
+    // Start of the grapheme cluster or word before index
+    fn previous(&self, index: usize, word: bool) -> usize {
+        let starts: Vec<_> = if word {
+            self.value
+                .split_word_bound_indices()
+                .filter(|(_, segment)| !segment.trim().is_empty())
+                .map(|(byte, _)| self.index(byte))
+                .collect()
+        } else {
+            self.value
+                .grapheme_indices(true)
+                .map(|(byte, _)| self.index(byte))
+                .collect()
+        };
+
+        starts
+            .into_iter()
+            .rfind(|&start| start < index)
+            .unwrap_or(0)
+    }
+
+    // End of the grapheme cluster or word after index
+    fn next(&self, index: usize, word: bool) -> usize {
+        let ends: Vec<_> = if word {
+            self.value
+                .split_word_bound_indices()
+                .filter(|(_, segment)| !segment.trim().is_empty())
+                .map(|(byte, segment)| self.index(byte + segment.len()))
+                .collect()
+        } else {
+            self.value
+                .grapheme_indices(true)
+                .map(|(byte, grapheme)| self.index(byte + grapheme.len()))
+                .collect()
+        };
+
+        ends.into_iter()
+            .find(|&end| end > index)
+            .unwrap_or(self.length())
+    }
+}
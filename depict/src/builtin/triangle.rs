@@ -1,9 +1,18 @@
 use depict_macro::shape;
+use glam::Vec2;
 
 use crate::{
-    core::renderable::Renderable,
-    engine::{properties::Properties, renderer::RenderBatch, shader::Vertex},
-    graphics::{asset::Assets, color::Color},
+    core::{
+        properties::{Background, Border, FillRule},
+        renderable::Renderable,
+    },
+    engine::{properties::Properties, renderer::RenderBatch},
+    graphics::{
+        asset::Assets,
+        color::Color,
+        gradient,
+        tessellation::{bounds, draw_bordered, signed_area},
+    },
 };
 
 shape!(
@@ -14,22 +23,37 @@ shape!(
         y2: f32,
         x3: f32,
         y3: f32,
-        color: Color = Color::CLEAR,
+        background: Background = Background::Color(Color::CLEAR),
+        border: Border = Border::NONE,
+        antialias: Option<bool> = None,
     }
 );
 
 impl Renderable for Triangle {
-    fn request(&self, _assets: &mut Assets, _properties: &Properties) {}
+    fn request(&self, assets: &mut Assets, _properties: &Properties) {
+        gradient::request(&self.background, assets);
+    }
 
-    fn render(&self, batch: &mut RenderBatch, _properties: &Properties) {
-        if self.color == Color::CLEAR {
-            return;
+    fn render(&self, batch: &mut RenderBatch, properties: &Properties) {
+        let feather = properties.feather(self.antialias);
+        let mut contour = [
+            Vec2::new(self.x1, self.y1),
+            Vec2::new(self.x2, self.y2),
+            Vec2::new(self.x3, self.y3),
+        ];
+        if signed_area(&contour) < 0.0 {
+            contour.reverse();
         }
 
-        batch.triangle(
-            Vertex::new(self.x1, self.y1, 0.0, 0.0, self.color, u32::MAX),
-            Vertex::new(self.x2, self.y2, 0.0, 0.0, self.color, u32::MAX),
-            Vertex::new(self.x3, self.y3, 0.0, 0.0, self.color, u32::MAX),
+        let area = bounds(contour);
+        draw_bordered(
+            batch,
+            &[contour.to_vec()],
+            FillRule::NonZero,
+            &self.background,
+            self.border,
+            area,
+            feather,
         );
     }
 }
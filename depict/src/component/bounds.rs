@@ -1,6 +1,22 @@
-pub struct BondingBox {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingBox {
     pub x: f32,
     pub y: f32,
     pub width: f32,
     pub height: f32,
 }
+
+impl BoundingBox {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+}
@@ -1,16 +1,20 @@
 use std::{collections::HashMap, sync::Arc};
 
+use arboard::Clipboard;
 use fontdue::FontSettings;
 use image::DynamicImage;
 use wgpu::Queue;
-use winit::window::Window;
+use winit::{dpi::PhysicalPosition, window::Window};
 
 use crate::{
-    component::{dimension::Dimension, memory::Memory, time::Time},
+    builtin::text::{Text, TextLayout, TextMeasurement},
+    component::{dimension::Dimension, memory::Memory, screen::factors, time::Time},
     engine::{properties::Properties, renderer::Renderer, size::Size},
     graphics::{
         asset::{Asset, Assets, Font, Image},
-        font::{FontEmphasis, FontThickness},
+        bitmap::{BitmapFace, BitmapFontError},
+        font::{self, DecorationMetrics, Face, FontEmphasis, FontThickness},
+        variable::VariableFace,
     },
     input::{keyboard::Keyboard, mouse::Mouse, tracker::Tracker},
 };
@@ -19,7 +23,8 @@ use super::renderable::Renderable;
 
 pub struct PartialContext {
     pub(crate) img_sources: Vec<(u32, Memory<DynamicImage>)>,
-    pub(crate) font_sources: Vec<(u32, HashMap<(FontThickness, FontEmphasis), fontdue::Font>)>,
+    pub(crate) font_sources: Vec<font::Font>,
+    pub(crate) system_fonts: Option<fontdb::Database>,
     pub size: Dimension<f32>,
 }
 
@@ -33,22 +38,159 @@ impl PartialContext {
         Asset::new(id)
     }
 
+    // Variable fonts are drawn at the exact weight and width of each glyph, so a single one can be
+    // given for every thickness
     pub fn font(
         &mut self,
         style_bytes: HashMap<(FontThickness, FontEmphasis), &[u8]>,
     ) -> Asset<Font> {
-        let id = self.font_sources.len() as u32;
-        let styles: HashMap<_, _> = style_bytes
-            .iter()
-            .map(|x| {
+        let styles = style_bytes
+            .into_iter()
+            .map(|(key, bytes)| {
                 (
-                    *x.0,
-                    fontdue::Font::from_bytes(*x.1, FontSettings::default()).unwrap(),
+                    key,
+                    Self::outline_face(bytes, 0).expect("Couldn't parse font"),
                 )
             })
             .collect();
 
-        self.font_sources.push((id, styles));
+        self.outline_font(styles)
+    }
+
+    // Installed family by name, such as "DejaVu Sans", with every weight and italic found for it.
+    // Returns None when no face of the family is installed, or none of them can be parsed.
+    pub fn system_font(&mut self, family: &str) -> Option<Asset<Font>> {
+        let database = self.system_fonts.get_or_insert_with(|| {
+            let mut database = fontdb::Database::new();
+            database.load_system_fonts();
+            database
+        });
+
+        // Several faces can land on the same style, like Book and Regular or condensed cuts,
+        // so try the one closest to a normal width first and prefer true italics over obliques
+        let mut matches: HashMap<(FontThickness, FontEmphasis), Vec<&fontdb::FaceInfo>> =
+            HashMap::new();
+        let rank = |face: &fontdb::FaceInfo| {
+            (
+                face.stretch
+                    .to_number()
+                    .abs_diff(fontdb::Stretch::Normal.to_number()),
+                face.style == fontdb::Style::Oblique,
+            )
+        };
+
+        for face in database.faces().filter(|face| {
+            face.families
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(family))
+        }) {
+            let emphasis = match face.style {
+                fontdb::Style::Normal => FontEmphasis::Regular,
+                fontdb::Style::Italic | fontdb::Style::Oblique => FontEmphasis::Italic,
+            };
+            let key = (FontThickness::from_weight(face.weight.0), emphasis);
+
+            matches.entry(key).or_default().push(face);
+        }
+
+        // Faces that can't be read or parsed give way to the next best match for their style
+        let styles: Vec<_> = matches
+            .into_iter()
+            .filter_map(|(key, mut faces)| {
+                faces.sort_by_key(|face| rank(face));
+                faces.into_iter().find_map(|face| {
+                    database
+                        .with_face_data(face.id, Self::outline_face)
+                        .flatten()
+                        .map(|parsed| (key, parsed))
+                })
+            })
+            .collect();
+
+        if styles.is_empty() {
+            return None;
+        }
+
+        Some(self.outline_font(styles))
+    }
+
+    // AngelCode BMFont descriptor (.fnt, text or binary) along with its page images in order
+    pub fn bitmap_font(
+        &mut self,
+        descriptor: &[u8],
+        pages: &[&[u8]],
+    ) -> Result<Asset<Font>, BitmapFontError> {
+        let pages = pages
+            .iter()
+            .map(|bytes| image::load_from_memory(bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.bitmap_face(BitmapFace::bmfont(descriptor, &pages)?))
+    }
+
+    // Sprite sheet split into columns by rows equal cells, holding characters row by row
+    pub fn sprite_font(
+        &mut self,
+        bytes: &[u8],
+        columns: u32,
+        rows: u32,
+        characters: &str,
+    ) -> Result<Asset<Font>, BitmapFontError> {
+        let image = image::load_from_memory(bytes)?;
+
+        Ok(self.bitmap_face(BitmapFace::grid(&image, columns, rows, characters)?))
+    }
+
+    // None when the bytes aren't a font fontdue can read
+    fn outline_face(bytes: &[u8], index: u32) -> Option<(Face, DecorationMetrics)> {
+        let face = match VariableFace::parse(bytes, index) {
+            Some(variable) => Face::Variable(variable),
+            None => {
+                let settings = FontSettings {
+                    collection_index: index,
+                    ..FontSettings::default()
+                };
+                Face::Outline(fontdue::Font::from_bytes(bytes, settings).ok()?)
+            }
+        };
+
+        Some((face, DecorationMetrics::parse(bytes, index)))
+    }
+
+    fn outline_font(
+        &mut self,
+        styles: Vec<((FontThickness, FontEmphasis), (Face, DecorationMetrics))>,
+    ) -> Asset<Font> {
+        let id = self.font_sources.len() as u32;
+
+        let decorations = styles
+            .iter()
+            .map(|(key, (_, decorations))| (*key, *decorations))
+            .collect();
+        let styles = styles
+            .into_iter()
+            .map(|(key, (face, _))| (key, face))
+            .collect();
+
+        self.font_sources.push(font::Font {
+            id,
+            styles,
+            decorations,
+        });
+
+        Asset::new(id)
+    }
+
+    // Bitmap fonts have a single style, other thicknesses and emphases are synthesized
+    fn bitmap_face(&mut self, face: BitmapFace) -> Asset<Font> {
+        let id = self.font_sources.len() as u32;
+        let key = (FontThickness::Regular, FontEmphasis::Regular);
+
+        self.font_sources.push(font::Font {
+            id,
+            styles: HashMap::from([(key, Face::Bitmap(face))]),
+            decorations: HashMap::from([(key, DecorationMetrics::FALLBACK)]),
+        });
 
         Asset::new(id)
     }
@@ -69,8 +211,10 @@ pub struct Context<'a> {
     pub mouse: &'a Tracker<Mouse>,
     pub keyboard: &'a Tracker<Keyboard>,
     pub time: &'a Time,
+    pub(crate) clipboard: &'a mut Option<Clipboard>,
     pub(crate) renderer: Option<&'a mut Renderer>,
     pub(crate) renderables: Vec<Box<dyn Renderable + 'static>>,
+    pub(crate) properties: Properties,
 }
 
 impl<'a> Context<'a> {
@@ -95,7 +239,42 @@ impl<'a> Context<'a> {
         );
     }
 
-    pub(crate) fn render(&mut self, queue: &Queue, properties: &Properties) {
+    pub fn measure_text(&self, text: &Text) -> TextMeasurement {
+        text.measure(&self.assets.fonts, &self.properties)
+    }
+
+    pub fn layout_text(&self, text: &Text) -> TextLayout {
+        text.arrange(&self.assets.fonts, &self.properties)
+    }
+
+    // Inverse of how Mouse maps the cursor into the scaled canvas
+    pub(crate) fn to_window(&self, x: f32, y: f32) -> PhysicalPosition<f32> {
+        let (width_factor, height_factor) = factors(
+            self.size.width,
+            self.size.height,
+            self.window_size.width as f32,
+            self.window_size.height as f32,
+        );
+
+        PhysicalPosition::new(
+            x / width_factor + (1.0 - width_factor) * self.window_size.width as f32 * 0.5,
+            y / height_factor - (1.0 - height_factor) * self.window_size.height as f32 * 0.5,
+        )
+    }
+
+    pub fn clipboard(&mut self) -> Option<String> {
+        self.clipboard.as_mut()?.get_text().ok()
+    }
+
+    pub fn set_clipboard(&mut self, text: &str) {
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            // Nothing sensible to do when another program holds onto the clipboard
+            let _ = clipboard.set_text(text);
+        }
+    }
+
+    pub(crate) fn render(&mut self, queue: &Queue) {
+        let properties = self.properties;
         let renderer = self.renderer.as_mut().unwrap();
 
         // Putting true on TANKS performance since it is a double for loop
@@ -112,6 +291,7 @@ impl<'a> Context<'a> {
 
         batch.assets.images.update(&queue);
         batch.assets.fonts.atlas.update(&queue);
+        batch.assets.gradients.atlas.update(queue);
 
         for renderable in &self.renderables {
             renderable.render(&mut batch, &properties);
@@ -119,6 +299,8 @@ impl<'a> Context<'a> {
         batch.finish();
 
         batch.assets.fonts.atlas.sweep();
+        batch.assets.gradients.sweep();
+        batch.assets.fills.sweep();
     }
 
     pub fn show_cursor(&mut self) {
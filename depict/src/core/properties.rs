@@ -3,10 +3,118 @@ use crate::graphics::{
     color::Color,
 };
 
-#[derive(Debug, Copy, Clone)]
+// Gradient stops are (position, color) pairs, with positions from 0.0 to 1.0 along the gradient.
+// They're sorted before drawing, and the ends keep the nearest stop's color.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Background {
     Color(Color),
     Image(Asset<Image>),
+    // Angle in radians, clockwise from left to right, spanning the bounding box corner to corner
+    LinearGradient {
+        angle: f32,
+        stops: Vec<(f32, Color)>,
+    },
+    // Center as a fraction of the bounding box, and radius as a fraction of half its size on
+    // each axis, so 1.0 reaches the sides
+    RadialGradient {
+        center: (f32, f32),
+        radius: f32,
+        stops: Vec<(f32, Color)>,
+    },
+    // Sweeps clockwise around the center, starting at the angle in radians
+    ConicGradient {
+        center: (f32, f32),
+        angle: f32,
+        stops: Vec<(f32, Color)>,
+    },
+}
+
+impl Background {
+    pub(crate) fn stops(&self) -> Option<&[(f32, Color)]> {
+        match self {
+            Background::Color(_) | Background::Image(_) => None,
+            Background::LinearGradient { stops, .. }
+            | Background::RadialGradient { stops, .. }
+            | Background::ConicGradient { stops, .. } => Some(stops),
+        }
+    }
+}
+
+// Which areas of overlapping or self-intersecting outlines get filled
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    pub(crate) fn inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+// Dashes alternate between drawn and skipped lengths, starting dash_offset into the pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub color: Color,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: f32,
+    pub dashes: Vec<f32>,
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    pub fn new(width: f32, color: Color) -> Self {
+        Self {
+            width,
+            color,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn with_dashes(mut self, dashes: Vec<f32>, dash_offset: f32) -> Self {
+        self.dashes = dashes;
+        self.dash_offset = dash_offset;
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -14,6 +122,16 @@ pub enum Align {
     Left,
     Center,
     Right,
+    Justify,
+}
+
+// Positions text within its max_height, or around y when there isn't one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+    Baseline,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -22,15 +140,120 @@ pub enum OverflowBreak {
     Character,
 }
 
+// Lines that don't fit entirely inside the height limit are never drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextOverflow {
+    Clip,
+    Ellipsis,
+    Fade,
+}
+
+// Vertical modes stack upright glyphs in columns, with x and y at the top right corner for
+// VerticalRl and the top left for VerticalLr. Everything about lines turns with them, so width
+// limits column height, max_height limits how far the columns spread, and Align::Left is the top.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WritingMode {
+    Horizontal,
+    VerticalRl,
+    VerticalLr,
+}
+
+// Radii of a rectangle's corners, all shrunk by the same factor when neighbors don't fit, like CSS
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CornerRadius {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadius {
+    pub const NONE: Self = Self::all(0.0);
+
+    pub const fn all(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+}
+
+// Where a border sits relative to the edge of a shape. Text outlines always straddle the glyphs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BorderAlign {
+    Inside,
+    Center,
+    Outside,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Border {
     pub thickness: f32,
     pub color: Color,
+    pub align: BorderAlign,
 }
 
 impl Border {
     pub const NONE: Self = Self {
         thickness: 0.0,
         color: Color::CLEAR,
+        align: BorderAlign::Inside,
     };
+
+    pub fn new(thickness: f32, color: Color) -> Self {
+        Self {
+            thickness,
+            color,
+            align: BorderAlign::Inside,
+        }
+    }
+
+    pub fn with_align(mut self, align: BorderAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    // How far the inner and outer edges sit outside the shape's edge
+    pub(crate) fn extent(&self) -> (f32, f32) {
+        match self.align {
+            BorderAlign::Inside => (-self.thickness, 0.0),
+            BorderAlign::Center => (-self.thickness / 2.0, self.thickness / 2.0),
+            BorderAlign::Outside => (0.0, self.thickness),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Shadow {
+    pub x: f32,
+    pub y: f32,
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl Shadow {
+    pub fn new(x: f32, y: f32, color: Color) -> Self {
+        Self {
+            x,
+            y,
+            blur: 0.0,
+            color,
+        }
+    }
+
+    pub fn with_blur(mut self, blur: f32) -> Self {
+        self.blur = blur;
+        self
+    }
 }
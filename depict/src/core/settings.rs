@@ -14,6 +14,10 @@ pub struct Settings {
     pub visible: bool,
     pub transparent: bool,
     pub active: bool,
+    // Softens shape edges, unless a shape turns it off or on for itself
+    pub antialias: bool,
+    // Samples per pixel, lowered to what the graphics card supports
+    pub msaa: u32,
     // pub keep_aspect_ratio: bool,
     // pub scale_up: bool,
 }
@@ -32,6 +36,8 @@ impl Default for Settings {
             visible: true,
             transparent: false,
             active: true,
+            antialias: false,
+            msaa: 1,
             // keep_aspect_ratio: true,
             // scale_up: true,
         }
@@ -94,6 +100,16 @@ impl Settings {
         self
     }
 
+    pub fn with_antialias(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    pub fn with_msaa(mut self, samples: u32) -> Self {
+        self.msaa = samples;
+        self
+    }
+
     /*
     pub fn with_keep_aspect_ratio(mut self, keep_aspect_ratio: bool) -> Self {
         self.keep_aspect_ratio = keep_aspect_ratio;
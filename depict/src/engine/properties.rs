@@ -1,4 +1,17 @@
 #[derive(Debug, Copy, Clone)]
 pub struct Properties {
     pub scale_factor: f32,
+    pub antialias: bool,
+}
+
+impl Properties {
+    // Width shape edges fade out over, a physical pixel, with a shape's own setting taking
+    // priority over the global one
+    pub(crate) fn feather(&self, antialias: Option<bool>) -> f32 {
+        if antialias.unwrap_or(self.antialias) {
+            1.0 / self.scale_factor
+        } else {
+            0.0
+        }
+    }
 }
@@ -92,6 +92,7 @@ impl Shader {
         &self,
         device: &Device,
         bind_group_layouts: &[&BindGroupLayout],
+        sample_count: u32,
     ) -> RenderPipeline {
         let mut os_path = current_dir().unwrap();
         os_path.push("depict/src/");
@@ -147,7 +148,7 @@ impl Shader {
             },
             depth_stencil: None,
             multisample: MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: 1,
                 alpha_to_coverage_enabled: false,
             },
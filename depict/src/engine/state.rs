@@ -1,15 +1,17 @@
 use std::{cell::RefCell, collections::HashMap, iter, rc::Rc, sync::Arc, time::SystemTime};
 
+use arboard::Clipboard;
 use bytemuck::cast_slice;
 use glam::{Affine2, Mat4};
 use wgpu::{
     Backends, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingResource, BindingType, Buffer, Color, CommandEncoderDescriptor,
-    Device, DeviceDescriptor, Features, IndexFormat, Instance, InstanceDescriptor, Limits, LoadOp,
-    Operations, PowerPreference, Queue, RenderPassColorAttachment, RenderPassDescriptor,
-    RenderPipeline, RequestAdapterOptionsBase, SamplerBindingType, ShaderStages, StoreOp, Surface,
-    SurfaceConfiguration, SurfaceError, SurfaceTargetUnsafe, TextureSampleType, TextureUsages,
-    TextureViewDescriptor, TextureViewDimension,
+    Device, DeviceDescriptor, Extent3d, Features, IndexFormat, Instance, InstanceDescriptor,
+    Limits, LoadOp, Operations, PowerPreference, Queue, RenderPassColorAttachment,
+    RenderPassDescriptor, RenderPipeline, RequestAdapterOptionsBase, SamplerBindingType,
+    ShaderStages, StoreOp, Surface, SurfaceConfiguration, SurfaceError, SurfaceTargetUnsafe,
+    TextureDescriptor, TextureDimension, TextureFormatFeatureFlags, TextureSampleType,
+    TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
 };
 use winit::window::Window;
 
@@ -23,7 +25,8 @@ use crate::{
     graphics::{
         asset::{Assets, FontAsset},
         atlas::Atlas,
-        font,
+        gradient::GradientAsset,
+        tessellation::FillCache,
     },
     input::{keyboard::Keyboard, mouse::Mouse, tracker::Tracker},
 };
@@ -47,18 +50,23 @@ pub(crate) struct State<'a> {
     pub(crate) queue: Queue,
     pub(crate) config: SurfaceConfiguration,
     pub(crate) window: Arc<Window>,
+    sample_count: u32,
+    // Drawn into instead of the surface when multisampling, then resolved onto it
+    multisampled: Option<TextureView>,
     count: u32,
     vertex_buffer: Buffer,
     index_buffer: Buffer,
     pipeline: RenderPipeline,
     texture_bind_group: BindGroup,
     font_bind_group: BindGroup,
+    gradient_bind_group: BindGroup,
     uniforms: Uniforms,
     pub(crate) properties: Properties,
     pub(crate) mouse: Tracker<Mouse>,
     pub(crate) keyboard: Tracker<Keyboard>,
     pub(crate) clear_color: Color,
     pub(crate) time: Time,
+    clipboard: Option<Clipboard>,
     system: Rc<RefCell<dyn System<'a>>>,
 }
 
@@ -90,7 +98,9 @@ impl<'a> State<'a> {
         let (device, queue) = adapter
             .request_device(
                 &DeviceDescriptor {
-                    required_features: Features::empty(),
+                    // Lets sample counts other than 4 be used when the adapter supports them
+                    required_features: adapter.features()
+                        & Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                     required_limits: Limits::default(),
                     label: Some("Device"),
                 },
@@ -120,9 +130,31 @@ impl<'a> State<'a> {
         };
         surface.configure(&device, &config);
 
+        // Falls back to the most samples the format supports, up to the requested count
+        let format_features = if device
+            .features()
+            .contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            adapter.get_texture_format_features(config.format)
+        } else {
+            config.format.guaranteed_format_features(device.features())
+        };
+        let sample_count = [16, 8, 4, 2]
+            .into_iter()
+            .find(|&count| {
+                count <= settings.msaa
+                    && format_features.flags.sample_count_supported(count)
+                    && format_features
+                        .flags
+                        .contains(TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+            })
+            .unwrap_or(1);
+        let multisampled = multisampled(&device, &config, sample_count);
+
         let mut ctx = PartialContext {
             img_sources: Vec::new(),
             font_sources: Vec::new(),
+            system_fonts: None,
             size: Dimension::new(size.width as f32, size.height as f32),
         };
 
@@ -212,15 +244,50 @@ impl<'a> State<'a> {
             ],
         });
 
-        let mut fonts = HashMap::new();
-        for source in ctx.font_sources {
-            fonts.insert(
-                source.0,
-                font::Font {
-                    id: source.0,
-                    styles: source.1,
+        // Ramps are baked while rendering, like glyphs
+        let gradient_atlas = Atlas::new(&device, initial_size, max_size);
+
+        let gradient_bind_group_layout =
+            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Gradient Bind Group Layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: TextureViewDimension::D2,
+                            sample_type: TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let gradient_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Gradient Bind Group"),
+            layout: &gradient_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gradient_atlas.view),
                 },
-            );
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&gradient_atlas.sampler),
+                },
+            ],
+        });
+
+        let mut fonts = HashMap::new();
+        for font in ctx.font_sources {
+            fonts.insert(font.id, font);
         }
 
         let assets = Assets {
@@ -230,6 +297,8 @@ impl<'a> State<'a> {
                 data: HashMap::new(),
                 atlas: font_atlas,
             },
+            gradients: GradientAsset::new(gradient_atlas),
+            fills: FillCache::default(),
         };
 
         let mut uniform_layout_entries = Vec::new();
@@ -269,11 +338,14 @@ impl<'a> State<'a> {
                 &uniform_bind_group_layout,
                 &texture_bind_group_layout,
                 &font_bind_group_layout,
+                &gradient_bind_group_layout,
             ],
+            sample_count,
         );
 
         let properties = Properties {
             scale_factor: window.scale_factor() as f32,
+            antialias: settings.antialias,
         };
 
         let mouse = Tracker::new(Mouse::new());
@@ -293,12 +365,15 @@ impl<'a> State<'a> {
             queue,
             config,
             window,
+            sample_count,
+            multisampled,
             count,
             vertex_buffer,
             index_buffer,
             pipeline,
             texture_bind_group,
             font_bind_group,
+            gradient_bind_group,
             uniforms,
             properties,
             mouse,
@@ -312,6 +387,7 @@ impl<'a> State<'a> {
             time: Time {
                 start: SystemTime::now(),
             },
+            clipboard: Clipboard::new().ok(),
             system,
         }
     }
@@ -327,13 +403,15 @@ impl<'a> State<'a> {
             mouse: &self.mouse,
             keyboard: &self.keyboard,
             time: &self.time,
+            clipboard: &mut self.clipboard,
             renderer: Some(&mut renderer),
             renderables: Vec::new(),
             window: self.window.clone(),
+            properties: self.properties,
         };
 
         self.system.borrow_mut().render(&mut ctx);
-        ctx.render(&self.queue, &self.properties);
+        ctx.render(&self.queue);
 
         renderer.build(&self.device)
     }
@@ -347,9 +425,11 @@ impl<'a> State<'a> {
             mouse: &self.mouse,
             keyboard: &self.keyboard,
             time: &self.time,
+            clipboard: &mut self.clipboard,
             renderer: None,
             renderables: Vec::new(),
             window: self.window.clone(),
+            properties: self.properties,
         });
     }
 
@@ -368,12 +448,22 @@ impl<'a> State<'a> {
                 label: Some("Command Encoder"),
             });
 
-        let color_attatchment = RenderPassColorAttachment {
-            view: &image_view,
-            resolve_target: None,
-            ops: Operations {
-                load: LoadOp::Clear(self.clear_color),
-                store: StoreOp::Store,
+        let color_attatchment = match &self.multisampled {
+            Some(view) => RenderPassColorAttachment {
+                view,
+                resolve_target: Some(&image_view),
+                ops: Operations {
+                    load: LoadOp::Clear(self.clear_color),
+                    store: StoreOp::Discard,
+                },
+            },
+            None => RenderPassColorAttachment {
+                view: &image_view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(self.clear_color),
+                    store: StoreOp::Store,
+                },
             },
         };
 
@@ -391,6 +481,7 @@ impl<'a> State<'a> {
             render_pass.set_bind_group(0, &self.uniforms.bind_group, &[]);
             render_pass.set_bind_group(1, &self.texture_bind_group, &[]);
             render_pass.set_bind_group(2, &self.font_bind_group, &[]);
+            render_pass.set_bind_group(3, &self.gradient_bind_group, &[]);
 
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
             render_pass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint16);
@@ -410,6 +501,7 @@ impl<'a> State<'a> {
             (self.config.width, self.config.height) = (new_size.width, new_size.height);
 
             self.surface.configure(&self.device, &self.config);
+            self.multisampled = multisampled(&self.device, &self.config, self.sample_count);
 
             self.screen = fit(
                 self.size.width as f32,
@@ -437,3 +529,30 @@ impl<'a> State<'a> {
         self.resize(self.window_size);
     }
 }
+
+fn multisampled(
+    device: &Device,
+    config: &SurfaceConfiguration,
+    sample_count: u32,
+) -> Option<TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some("Multisampled Texture"),
+        size: Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: TextureDimension::D2,
+        format: config.format,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+
+    Some(texture.create_view(&TextureViewDescriptor::default()))
+}
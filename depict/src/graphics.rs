@@ -1,6 +1,11 @@
 pub mod asset;
 pub mod atlas;
+pub mod bitmap;
 pub mod color;
 pub mod font;
 pub mod glyph;
+pub mod gradient;
 pub mod image;
+pub mod markup;
+pub mod tessellation;
+pub mod variable;
@@ -3,7 +3,14 @@ use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
 use fontdue::Metrics;
 use image::{DynamicImage, RgbaImage};
 
-use super::{atlas::Atlas, glyph::TextRenderingData};
+use super::{
+    atlas::Atlas,
+    font::DecorationMetrics,
+    font::{FontEmphasis, FontThickness},
+    glyph::{is_invisible, is_newline, spread, Glyph, TextRenderingData},
+    gradient::GradientAsset,
+    tessellation::FillCache,
+};
 use crate::graphics::font;
 
 #[derive(Debug)]
@@ -14,29 +21,122 @@ pub struct FontAsset {
 }
 
 impl FontAsset {
+    // Picks the first font in the chain that contains the character, falling back to the first
+    // font's .notdef box if none of them do
+    pub(crate) fn resolve(
+        &self,
+        character: char,
+        chain: &[u32],
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> u32 {
+        if character.is_control() {
+            return chain[0];
+        }
+
+        chain
+            .iter()
+            .copied()
+            .find(|id| {
+                self.fonts
+                    .get(id)
+                    .is_some_and(|font| font.has_glyph(character, thickness, emphasis))
+            })
+            .unwrap_or(chain[0])
+    }
+
+    pub(crate) fn ascent(
+        &self,
+        font_id: u32,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+        size: f32,
+    ) -> f32 {
+        self.fonts
+            .get(&font_id)
+            .and_then(|font| font.style(thickness, emphasis))
+            .and_then(|(style, _)| style.ascent(size))
+            .unwrap_or(size)
+    }
+
+    pub(crate) fn decorations(
+        &self,
+        font_id: u32,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> DecorationMetrics {
+        self.fonts
+            .get(&font_id)
+            .map_or(DecorationMetrics::FALLBACK, |font| {
+                font.decorations(thickness, emphasis)
+            })
+    }
+
+    // Same metrics update would produce, without rasterizing anything into the atlas
+    pub(crate) fn measure(&self, glyph: &Glyph) -> Metrics {
+        if is_newline(glyph.character) {
+            return Metrics::default();
+        }
+
+        let font = &self.fonts[&glyph.font_id];
+        let (font_style, synthesis) = font
+            .style(glyph.thickness, glyph.emphasis)
+            .expect("Font has no styles loaded");
+
+        let (character, advances) = Self::substitute(glyph.character);
+        let mut metrics = synthesis.metrics(
+            font_style.metrics(character, glyph.size, glyph.variation()),
+            glyph.size,
+        );
+        if !advances {
+            metrics.advance_width = 0.0;
+        }
+
+        metrics
+    }
+
+    // Tabs are measured as a space so tab stops can be counted in spaces, and zero-width
+    // characters are drawn as a space that doesn't move the pen
+    fn substitute(character: char) -> (char, bool) {
+        match character {
+            '\t' => (' ', true),
+            character if is_invisible(character) => (' ', false),
+            character => (character, true),
+        }
+    }
+
     pub(crate) fn update(&mut self) {
         for (_, TextRenderingData { glyphs, metrics }) in &mut self.data {
-            'outer: for idx in 0..glyphs.len() {
-                for i in 0..idx {
-                    if glyphs[idx] == glyphs[i] {
-                        glyphs[idx] = glyphs[i].clone();
-                        metrics.push(metrics[i]);
-                        continue 'outer;
-                    }
+            let mut seen: HashMap<_, usize> = HashMap::new();
+            for idx in 0..glyphs.len() {
+                if let Some(&i) = seen.get(&glyphs[idx].key()) {
+                    glyphs[idx].image_id = glyphs[i].image_id;
+                    metrics.push(metrics[i]);
+                    continue;
                 }
+                seen.insert(glyphs[idx].key(), idx);
 
                 let glyph = &mut glyphs[idx];
 
-                // Maybe eventually add form feed & vertical tab?
-                if glyph.character == '\n' || glyph.character == '\r' {
+                if is_newline(glyph.character) {
                     metrics.push(Metrics::default());
                     continue;
                 }
 
                 let font = &self.fonts[&glyph.font_id];
-                let font_style = &font.styles[&(glyph.thickness, glyph.emphasis)];
-
-                let (font_metrics, bitmap) = font_style.rasterize(glyph.character, glyph.size);
+                let (font_style, synthesis) = font
+                    .style(glyph.thickness, glyph.emphasis)
+                    .expect("Font has no styles loaded");
+
+                let (character, advances) = Self::substitute(glyph.character);
+                let (font_metrics, bitmap) =
+                    font_style.rasterize(character, glyph.size, glyph.variation());
+                let (font_metrics, bitmap) = synthesis.apply(font_metrics, bitmap, glyph.size);
+                let (mut font_metrics, bitmap) =
+                    spread(font_metrics, bitmap, glyph.outline, glyph.blur);
+                if !advances {
+                    font_metrics.advance_width = 0.0;
+                }
                 metrics.push(font_metrics);
 
                 let width = font_metrics.width;
@@ -69,6 +169,8 @@ impl FontAsset {
 pub struct Assets {
     pub images: Atlas,
     pub fonts: FontAsset,
+    pub(crate) gradients: GradientAsset,
+    pub(crate) fills: FillCache,
 }
 
 pub trait AssetType {}
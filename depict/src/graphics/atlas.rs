@@ -194,6 +194,17 @@ impl Atlas {
         id
     }
 
+    // Frees the source's space, which is repacked on the next update
+    pub(crate) fn remove(&mut self, id: u32) {
+        let count = self.sources.len();
+        self.sources.retain(|(source, _)| *source != id);
+
+        if self.sources.len() != count {
+            self.images.remove(&id);
+            self.edited = true;
+        }
+    }
+
     pub fn sweep(&mut self) {
         for i in (0..self.sources.len()).rev() {
             if !self.sources[i].1.remembered {
@@ -0,0 +1,444 @@
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use fontdue::{Metrics, OutlineBounds};
+use image::DynamicImage;
+
+#[derive(Debug)]
+pub enum BitmapFontError {
+    InvalidDescriptor(String),
+    MissingPage(u32),
+    InvalidGrid(String),
+    Image(image::ImageError),
+}
+
+impl Display for BitmapFontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BitmapFontError::InvalidDescriptor(reason) => {
+                write!(f, "Invalid BMFont descriptor: {}", reason)
+            }
+            BitmapFontError::MissingPage(page) => write!(f, "Page {} wasn't given", page),
+            BitmapFontError::InvalidGrid(reason) => write!(f, "Invalid sprite grid: {}", reason),
+            BitmapFontError::Image(error) => write!(f, "Couldn't load page: {}", error),
+        }
+    }
+}
+
+impl Error for BitmapFontError {}
+
+impl From<image::ImageError> for BitmapFontError {
+    fn from(error: image::ImageError) -> Self {
+        BitmapFontError::Image(error)
+    }
+}
+
+// Offsets are from the top of the line, as BMFont stores them
+#[derive(Debug, Clone)]
+struct BitmapGlyph {
+    width: usize,
+    height: usize,
+    x_offset: f32,
+    y_offset: f32,
+    advance: f32,
+    coverage: Vec<u8>,
+}
+
+// Glyphs cut out of images, drawn as masks tinted by the text color and scaled from the size
+// they were drawn at with nearest neighbor sampling
+#[derive(Debug, Clone)]
+pub struct BitmapFace {
+    size: f32,
+    base: f32,
+    glyphs: HashMap<char, BitmapGlyph>,
+}
+
+impl BitmapFace {
+    // Fixed grid of cells read left to right, top to bottom, one per character
+    pub(crate) fn grid(
+        image: &DynamicImage,
+        columns: u32,
+        rows: u32,
+        characters: &str,
+    ) -> Result<Self, BitmapFontError> {
+        let invalid = |reason: &str| BitmapFontError::InvalidGrid(reason.to_string());
+        if columns == 0 || rows == 0 {
+            return Err(invalid("columns and rows must be at least 1"));
+        }
+
+        let (cell_width, cell_height) = (image.width() / columns, image.height() / rows);
+        if cell_width == 0 || cell_height == 0 {
+            return Err(invalid("cells are smaller than a pixel"));
+        }
+        let cells = columns.saturating_mul(rows) as usize;
+        let sheet = Sheet::new(image);
+
+        let glyphs = characters
+            .chars()
+            .take(cells)
+            .enumerate()
+            .map(|(idx, character)| {
+                let (column, row) = (idx as u32 % columns, idx as u32 / columns);
+                let coverage = sheet
+                    .cut(
+                        column * cell_width,
+                        row * cell_height,
+                        cell_width,
+                        cell_height,
+                        Channel::All,
+                    )
+                    .ok_or_else(|| invalid("cell lies outside the image"))?;
+                let glyph = BitmapGlyph {
+                    width: cell_width as usize,
+                    height: cell_height as usize,
+                    x_offset: 0.0,
+                    y_offset: 0.0,
+                    advance: cell_width as f32,
+                    coverage,
+                };
+                Ok((character, glyph))
+            })
+            .collect::<Result<_, BitmapFontError>>()?;
+
+        Ok(Self {
+            size: cell_height as f32,
+            base: cell_height as f32,
+            glyphs,
+        })
+    }
+
+    // AngelCode BMFont descriptor, in either the text or binary format, with its pages in order
+    pub(crate) fn bmfont(
+        descriptor: &[u8],
+        pages: &[DynamicImage],
+    ) -> Result<Self, BitmapFontError> {
+        let font = if descriptor.starts_with(b"BMF") {
+            Descriptor::binary(descriptor)?
+        } else {
+            let text = std::str::from_utf8(descriptor)
+                .map_err(|_| BitmapFontError::InvalidDescriptor("not UTF-8".to_string()))?;
+            Descriptor::text(text)?
+        };
+
+        let sheets: Vec<_> = pages.iter().map(Sheet::new).collect();
+
+        let mut glyphs = HashMap::new();
+        for char in font.chars {
+            let Some(character) = char::from_u32(char.id) else {
+                continue;
+            };
+            let sheet = sheets
+                .get(char.page as usize)
+                .ok_or(BitmapFontError::MissingPage(char.page))?;
+            let coverage = sheet
+                .cut(
+                    char.x,
+                    char.y,
+                    char.width,
+                    char.height,
+                    Channel::from_bits(char.channel),
+                )
+                .ok_or_else(|| {
+                    BitmapFontError::InvalidDescriptor(format!(
+                        "char {} lies outside page {}",
+                        char.id, char.page
+                    ))
+                })?;
+
+            glyphs.insert(
+                character,
+                BitmapGlyph {
+                    width: char.width as usize,
+                    height: char.height as usize,
+                    x_offset: char.x_offset as f32,
+                    y_offset: char.y_offset as f32,
+                    advance: char.advance as f32,
+                    coverage,
+                },
+            );
+        }
+
+        Ok(Self {
+            size: font.size,
+            base: font.base,
+            glyphs,
+        })
+    }
+
+    pub(crate) fn has_glyph(&self, character: char) -> bool {
+        self.glyphs.contains_key(&character)
+    }
+
+    pub(crate) fn ascent(&self, size: f32) -> f32 {
+        self.base * size / self.size
+    }
+
+    pub(crate) fn metrics(&self, character: char, size: f32) -> Metrics {
+        let Some(glyph) = self.glyph(character) else {
+            return Metrics::default();
+        };
+
+        let scale = size / self.size;
+        let width = (glyph.width as f32 * scale).round();
+        let height = (glyph.height as f32 * scale).round();
+        let xmin = (glyph.x_offset * scale).round();
+        let ymin = ((self.base - glyph.y_offset - glyph.height as f32) * scale).round();
+
+        Metrics {
+            xmin: xmin as i32,
+            ymin: ymin as i32,
+            width: width as usize,
+            height: height as usize,
+            advance_width: glyph.advance * scale,
+            advance_height: 0.0,
+            bounds: OutlineBounds {
+                xmin,
+                ymin,
+                width,
+                height,
+            },
+        }
+    }
+
+    pub(crate) fn rasterize(&self, character: char, size: f32) -> (Metrics, Vec<u8>) {
+        let metrics = self.metrics(character, size);
+        let Some(glyph) = self.glyph(character) else {
+            return (metrics, Vec::new());
+        };
+
+        let mut bitmap = vec![0; metrics.width * metrics.height];
+        for y in 0..metrics.height {
+            let source_y = y * glyph.height / metrics.height;
+            for x in 0..metrics.width {
+                let source_x = x * glyph.width / metrics.width;
+                bitmap[x + y * metrics.width] = glyph.coverage[source_x + source_y * glyph.width];
+            }
+        }
+
+        (metrics, bitmap)
+    }
+
+    // Characters outside the font fall back to '?', like a .notdef box would
+    fn glyph(&self, character: char) -> Option<&BitmapGlyph> {
+        self.glyphs
+            .get(&character)
+            .or_else(|| self.glyphs.get(&'?'))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Channel {
+    All,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl Channel {
+    // BMFont's chnl field, where glyphs may be packed into a single channel each
+    fn from_bits(bits: u8) -> Self {
+        match bits {
+            1 => Channel::Blue,
+            2 => Channel::Green,
+            4 => Channel::Red,
+            8 => Channel::Alpha,
+            _ => Channel::All,
+        }
+    }
+}
+
+struct Sheet {
+    image: image::RgbaImage,
+    // Sheets without any transparency are read by brightness instead of alpha
+    opaque: bool,
+}
+
+impl Sheet {
+    fn new(image: &DynamicImage) -> Self {
+        let image = image.to_rgba8();
+        let opaque = image.pixels().all(|pixel| pixel[3] == 255);
+        Self { image, opaque }
+    }
+
+    // None when the rectangle doesn't fit inside the image
+    fn cut(&self, x: u32, y: u32, width: u32, height: u32, channel: Channel) -> Option<Vec<u8>> {
+        let right = x
+            .checked_add(width)
+            .filter(|&right| right <= self.image.width())?;
+        let bottom = y
+            .checked_add(height)
+            .filter(|&bottom| bottom <= self.image.height())?;
+
+        let mut coverage = Vec::with_capacity(width as usize * height as usize);
+        for row in y..bottom {
+            for column in x..right {
+                let [red, green, blue, alpha] = self.image.get_pixel(column, row).0;
+                coverage.push(match channel {
+                    Channel::Red => red,
+                    Channel::Green => green,
+                    Channel::Blue => blue,
+                    Channel::Alpha => alpha,
+                    Channel::All if self.opaque => {
+                        ((red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000) as u8
+                    }
+                    Channel::All => alpha,
+                });
+            }
+        }
+        Some(coverage)
+    }
+}
+
+struct DescriptorChar {
+    id: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    x_offset: i32,
+    y_offset: i32,
+    advance: i32,
+    page: u32,
+    channel: u8,
+}
+
+struct Descriptor {
+    size: f32,
+    base: f32,
+    chars: Vec<DescriptorChar>,
+}
+
+impl Descriptor {
+    fn text(text: &str) -> Result<Self, BitmapFontError> {
+        let mut size = None;
+        let mut base = None;
+        let mut chars = Vec::new();
+
+        for line in text.lines() {
+            let mut words = line.split_whitespace();
+            let Some(tag) = words.next() else {
+                continue;
+            };
+
+            let values: HashMap<&str, &str> =
+                words.filter_map(|word| word.split_once('=')).collect();
+            let number = |key: &str| -> Result<i32, BitmapFontError> {
+                values
+                    .get(key)
+                    .and_then(|value| value.trim_matches('"').parse().ok())
+                    .ok_or_else(|| {
+                        BitmapFontError::InvalidDescriptor(format!("{} is missing {}", tag, key))
+                    })
+            };
+            let unsigned = |key: &str| -> Result<u32, BitmapFontError> {
+                u32::try_from(number(key)?).map_err(|_| {
+                    BitmapFontError::InvalidDescriptor(format!("{} has a negative {}", tag, key))
+                })
+            };
+
+            match tag {
+                // Negative sizes mean the size matches the height of the characters
+                "info" => size = Some(number("size")?.unsigned_abs() as f32),
+                "common" => base = Some(number("base")? as f32),
+                "char" => chars.push(DescriptorChar {
+                    id: unsigned("id")?,
+                    x: unsigned("x")?,
+                    y: unsigned("y")?,
+                    width: unsigned("width")?,
+                    height: unsigned("height")?,
+                    x_offset: number("xoffset")?,
+                    y_offset: number("yoffset")?,
+                    advance: number("xadvance")?,
+                    page: if values.contains_key("page") {
+                        unsigned("page")?
+                    } else {
+                        0
+                    },
+                    channel: number("chnl").unwrap_or(15) as u8,
+                }),
+                _ => {}
+            }
+        }
+
+        Self::finish(size, base, chars)
+    }
+
+    // Version 3 of the binary format, described at https://www.angelcode.com/products/bmfont/doc/file_format.html
+    fn binary(bytes: &[u8]) -> Result<Self, BitmapFontError> {
+        let invalid = |reason: &str| BitmapFontError::InvalidDescriptor(reason.to_string());
+
+        if bytes.get(3) != Some(&3) {
+            return Err(invalid("only version 3 of the binary format is supported"));
+        }
+
+        let u16_at = |block: &[u8], at: usize| -> Result<u16, BitmapFontError> {
+            block
+                .get(at..at + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .ok_or_else(|| invalid("block is too short"))
+        };
+        let u32_at = |block: &[u8], at: usize| -> Result<u32, BitmapFontError> {
+            block
+                .get(at..at + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| invalid("block is too short"))
+        };
+
+        let mut size = None;
+        let mut base = None;
+        let mut chars = Vec::new();
+
+        let mut position = 4;
+        while position < bytes.len() {
+            let kind = bytes[position];
+            let length = u32_at(bytes, position + 1)? as usize;
+            let end = (position + 5)
+                .checked_add(length)
+                .ok_or_else(|| invalid("block runs past the end"))?;
+            let block = bytes
+                .get(position + 5..end)
+                .ok_or_else(|| invalid("block runs past the end"))?;
+            position = end;
+
+            match kind {
+                1 => size = Some((u16_at(block, 0)? as i16).unsigned_abs() as f32),
+                2 => base = Some(u16_at(block, 2)? as f32),
+                4 => {
+                    for char in block.chunks_exact(20) {
+                        chars.push(DescriptorChar {
+                            id: u32_at(char, 0)?,
+                            x: u16_at(char, 4)? as u32,
+                            y: u16_at(char, 6)? as u32,
+                            width: u16_at(char, 8)? as u32,
+                            height: u16_at(char, 10)? as u32,
+                            x_offset: u16_at(char, 12)? as i16 as i32,
+                            y_offset: u16_at(char, 14)? as i16 as i32,
+                            advance: u16_at(char, 16)? as i16 as i32,
+                            page: char[18] as u32,
+                            channel: char[19],
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self::finish(size, base, chars)
+    }
+
+    fn finish(
+        size: Option<f32>,
+        base: Option<f32>,
+        chars: Vec<DescriptorChar>,
+    ) -> Result<Self, BitmapFontError> {
+        let base = base.ok_or_else(|| {
+            BitmapFontError::InvalidDescriptor("common block is missing".to_string())
+        })?;
+
+        Ok(Self {
+            size: size.filter(|size| *size > 0.0).unwrap_or(base),
+            base,
+            chars,
+        })
+    }
+}
@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+use fontdue::Metrics;
+
+use super::{bitmap::BitmapFace, variable::VariableFace};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum FontThickness {
     Thin,
@@ -11,6 +15,41 @@ pub enum FontThickness {
     Bold,
     ExtraBold,
     Black,
+    // Any weight from 1 to 1000, drawn exactly by variable fonts and matched to the closest
+    // style otherwise
+    Weight(u16),
+}
+
+impl FontThickness {
+    pub fn weight(&self) -> u16 {
+        match self {
+            FontThickness::Thin => 100,
+            FontThickness::ExtraLight => 200,
+            FontThickness::Light => 300,
+            FontThickness::Regular => 400,
+            FontThickness::Medium => 500,
+            FontThickness::SemiBold => 600,
+            FontThickness::Bold => 700,
+            FontThickness::ExtraBold => 800,
+            FontThickness::Black => 900,
+            FontThickness::Weight(weight) => *weight,
+        }
+    }
+
+    // Nearest named thickness, for weights read from font files
+    pub fn from_weight(weight: u16) -> Self {
+        match weight.saturating_add(50) / 100 {
+            0 | 1 => FontThickness::Thin,
+            2 => FontThickness::ExtraLight,
+            3 => FontThickness::Light,
+            4 => FontThickness::Regular,
+            5 => FontThickness::Medium,
+            6 => FontThickness::SemiBold,
+            7 => FontThickness::Bold,
+            8 => FontThickness::ExtraBold,
+            _ => FontThickness::Black,
+        }
+    }
 }
 
 // Could I just make this a bool is_italic?
@@ -20,9 +59,288 @@ pub enum FontEmphasis {
     Italic,
 }
 
+// Position on a variable font's axes, with width as a percentage of normal. Static faces ignore it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Variation {
+    pub weight: f32,
+    pub width: f32,
+}
+
+// Outline faces are rasterized at any size, bitmap faces are scaled from their images
+#[derive(Debug)]
+pub enum Face {
+    Outline(fontdue::Font),
+    Variable(VariableFace),
+    Bitmap(BitmapFace),
+}
+
+impl Face {
+    pub(crate) fn has_glyph(&self, character: char) -> bool {
+        match self {
+            Face::Outline(font) => font.has_glyph(character),
+            Face::Variable(face) => face.has_glyph(character),
+            Face::Bitmap(face) => face.has_glyph(character),
+        }
+    }
+
+    pub(crate) fn metrics(&self, character: char, size: f32, variation: Variation) -> Metrics {
+        match self {
+            Face::Outline(font) => font.metrics(character, size),
+            Face::Variable(face) => face.metrics(character, size, variation),
+            Face::Bitmap(face) => face.metrics(character, size),
+        }
+    }
+
+    pub(crate) fn rasterize(
+        &self,
+        character: char,
+        size: f32,
+        variation: Variation,
+    ) -> (Metrics, Vec<u8>) {
+        match self {
+            Face::Outline(font) => font.rasterize(character, size),
+            Face::Variable(face) => face.rasterize(character, size, variation),
+            Face::Bitmap(face) => face.rasterize(character, size),
+        }
+    }
+
+    pub(crate) fn ascent(&self, size: f32) -> Option<f32> {
+        match self {
+            Face::Outline(font) => font.horizontal_line_metrics(size).map(|line| line.ascent),
+            Face::Variable(face) => Some(face.ascent(size)),
+            Face::Bitmap(face) => Some(face.ascent(size)),
+        }
+    }
+}
+
 // Is this necessary??
 #[derive(Debug)]
 pub struct Font {
     pub(crate) id: u32,
-    pub styles: HashMap<(FontThickness, FontEmphasis), fontdue::Font>,
+    pub styles: HashMap<(FontThickness, FontEmphasis), Face>,
+    pub(crate) decorations: HashMap<(FontThickness, FontEmphasis), DecorationMetrics>,
+}
+
+impl Font {
+    pub fn has_glyph(
+        &self,
+        character: char,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> bool {
+        self.style(thickness, emphasis)
+            .is_some_and(|(style, _)| style.has_glyph(character))
+    }
+
+    pub(crate) fn style(
+        &self,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> Option<(&Face, Synthesis)> {
+        let (key, mut synthesis) = self.matching(thickness, emphasis)?;
+        let face = &self.styles[&key];
+
+        // Weight axes draw real bold instead
+        if let Face::Variable(variable) = face {
+            synthesis.bold &= !variable.covers(thickness.weight());
+        }
+
+        Some((face, synthesis))
+    }
+
+    pub(crate) fn decorations(
+        &self,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> DecorationMetrics {
+        self.matching(thickness, emphasis)
+            .and_then(|(key, _)| self.decorations.get(&key))
+            .copied()
+            .unwrap_or(DecorationMetrics::FALLBACK)
+    }
+
+    // Closest loaded style, following https://www.w3.org/TR/css-fonts-4/#font-style-matching
+    fn matching(
+        &self,
+        thickness: FontThickness,
+        emphasis: FontEmphasis,
+    ) -> Option<((FontThickness, FontEmphasis), Synthesis)> {
+        let same_emphasis = self.styles.keys().any(|(_, e)| *e == emphasis);
+        let desired = thickness.weight();
+
+        let (matched_thickness, matched_emphasis) = self
+            .styles
+            .keys()
+            .filter(|(_, e)| !same_emphasis || *e == emphasis)
+            .min_by_key(|(t, _)| {
+                let weight = t.weight();
+                if (400..=500).contains(&desired) {
+                    if weight >= desired && weight <= 500 {
+                        (0, weight - desired)
+                    } else if weight < desired {
+                        (1, desired - weight)
+                    } else {
+                        (2, weight - desired)
+                    }
+                } else if desired < 400 {
+                    if weight <= desired {
+                        (0, desired - weight)
+                    } else {
+                        (1, weight - desired)
+                    }
+                } else if weight >= desired {
+                    (0, weight - desired)
+                } else {
+                    (1, desired - weight)
+                }
+            })
+            .copied()?;
+
+        let synthesis = Synthesis {
+            bold: desired >= 600 && matched_thickness.weight() < 600,
+            italic: emphasis == FontEmphasis::Italic && matched_emphasis != FontEmphasis::Italic,
+        };
+
+        Some(((matched_thickness, matched_emphasis), synthesis))
+    }
+}
+
+// Underline and strikeout as (offset of the top above the baseline, thickness), in ems
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct DecorationMetrics {
+    pub underline: (f32, f32),
+    pub strikeout: (f32, f32),
+}
+
+impl DecorationMetrics {
+    // Typical values, for fonts missing their post or OS/2 tables
+    pub const FALLBACK: Self = Self {
+        underline: (-0.1, 0.05),
+        strikeout: (0.3, 0.05),
+    };
+
+    pub fn parse(bytes: &[u8], index: u32) -> Self {
+        let Ok(face) = ttf_parser::Face::parse(bytes, index) else {
+            return Self::FALLBACK;
+        };
+
+        let em = face.units_per_em() as f32;
+        let line = |metrics: Option<ttf_parser::LineMetrics>, fallback| {
+            metrics.map_or(fallback, |metrics| {
+                (metrics.position as f32 / em, metrics.thickness as f32 / em)
+            })
+        };
+
+        Self {
+            underline: line(face.underline_metrics(), Self::FALLBACK.underline),
+            strikeout: line(face.strikeout_metrics(), Self::FALLBACK.strikeout),
+        }
+    }
+}
+
+// Faux styles applied to a rasterized glyph when the requested style isn't loaded
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Synthesis {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl Synthesis {
+    // Same horizontal shear fontconfig uses for oblique faces
+    const SKEW: f32 = 0.2;
+
+    fn strength(size: f32) -> usize {
+        f32::max((size / 24.0).round(), 1.0) as usize
+    }
+
+    fn shift(metrics: &Metrics) -> (f32, f32) {
+        let low = Self::SKEW * metrics.ymin as f32;
+        let high = Self::SKEW * (metrics.ymin as f32 + metrics.height as f32);
+        (low.floor(), high.ceil())
+    }
+
+    pub fn metrics(&self, mut metrics: Metrics, size: f32) -> Metrics {
+        if metrics.width == 0 || metrics.height == 0 {
+            if self.bold {
+                metrics.advance_width += Self::strength(size) as f32;
+            }
+            return metrics;
+        }
+
+        if self.bold {
+            let strength = Self::strength(size);
+            metrics.width += strength;
+            metrics.advance_width += strength as f32;
+            metrics.bounds.width += strength as f32;
+        }
+
+        if self.italic {
+            let (low, high) = Self::shift(&metrics);
+            metrics.width += (high - low) as usize + 1;
+            metrics.xmin += low as i32;
+            metrics.bounds.xmin += Self::SKEW * metrics.bounds.ymin;
+            metrics.bounds.width += Self::SKEW * metrics.bounds.height;
+        }
+
+        metrics
+    }
+
+    pub fn apply(&self, metrics: Metrics, bitmap: Vec<u8>, size: f32) -> (Metrics, Vec<u8>) {
+        if metrics.width == 0 || metrics.height == 0 {
+            return (self.metrics(metrics, size), bitmap);
+        }
+
+        let mut width = metrics.width;
+        let height = metrics.height;
+        let mut bitmap = bitmap;
+
+        if self.bold {
+            // Smear coverage to the right, which thickens vertical stems the most
+            let strength = Self::strength(size);
+            let bold_width = width + strength;
+            let mut bold = vec![0; bold_width * height];
+            for y in 0..height {
+                for x in 0..bold_width {
+                    let start = x.saturating_sub(strength);
+                    let end = usize::min(x + 1, width);
+                    bold[x + y * bold_width] = (start..end)
+                        .map(|i| bitmap[i + y * width])
+                        .max()
+                        .unwrap_or(0);
+                }
+            }
+
+            width = bold_width;
+            bitmap = bold;
+        }
+
+        if self.italic {
+            let (low, high) = Self::shift(&metrics);
+            let italic_width = width + (high - low) as usize + 1;
+            let mut italic = vec![0.0; italic_width * height];
+            for y in 0..height {
+                let above = metrics.ymin as f32 + (height - y) as f32 - 0.5;
+                let shift = Self::SKEW * above - low;
+                let whole = shift.floor() as usize;
+                let fraction = shift.fract();
+
+                for x in 0..width {
+                    let coverage = bitmap[x + y * width] as f32;
+                    italic[x + whole + y * italic_width] += coverage * (1.0 - fraction);
+                    italic[x + whole + 1 + y * italic_width] += coverage * fraction;
+                }
+            }
+
+            width = italic_width;
+            bitmap = italic
+                .iter()
+                .map(|coverage| f32::min(coverage.round(), 255.0) as u8)
+                .collect();
+        }
+
+        let metrics = self.metrics(metrics, size);
+        debug_assert_eq!(metrics.width, width);
+
+        (metrics, bitmap)
+    }
 }
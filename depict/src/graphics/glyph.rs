@@ -2,7 +2,7 @@ use fontdue::Metrics;
 
 use super::{
     color::Color,
-    font::{FontEmphasis, FontThickness},
+    font::{FontEmphasis, FontThickness, Variation},
 };
 
 #[derive(Debug, Copy, Clone)]
@@ -14,6 +14,21 @@ pub(crate) struct Glyph {
     pub color: Color,
     pub thickness: FontThickness,
     pub emphasis: FontEmphasis,
+    // Percentage of normal width, for variable fonts with a width axis
+    pub stretch: f32,
+    // Of the resolved font at this size, for placing glyphs upright in vertical text
+    pub ascent: f32,
+    pub decorations: Decorations,
+    // Grows the rasterized glyph, in pixels, for outlines and shadows
+    pub outline: f32,
+    pub blur: f32,
+}
+
+// Lines drawn along the glyph as (offset of the top above the baseline, thickness) in pixels
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub(crate) struct Decorations {
+    pub underline: Option<(f32, f32)>,
+    pub strikethrough: Option<(f32, f32)>,
 }
 
 impl PartialEq for Glyph {
@@ -24,6 +39,51 @@ impl PartialEq for Glyph {
             && self.color == other.color
             && self.thickness == other.thickness
             && self.emphasis == other.emphasis
+            && self.stretch == other.stretch
+            && self.outline == other.outline
+            && self.blur == other.blur
+    }
+}
+
+// Everything that changes how a glyph rasterizes, with floats by their bits so it can be hashed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphKey {
+    character: char,
+    font_id: u32,
+    size: u32,
+    color: [u32; 4],
+    thickness: FontThickness,
+    emphasis: FontEmphasis,
+    stretch: u32,
+    outline: u32,
+    blur: u32,
+}
+
+impl Glyph {
+    pub fn key(&self) -> GlyphKey {
+        GlyphKey {
+            character: self.character,
+            font_id: self.font_id,
+            size: self.size.to_bits(),
+            color: [
+                self.color.red.to_bits(),
+                self.color.green.to_bits(),
+                self.color.blue.to_bits(),
+                self.color.alpha.to_bits(),
+            ],
+            thickness: self.thickness,
+            emphasis: self.emphasis,
+            stretch: self.stretch.to_bits(),
+            outline: self.outline.to_bits(),
+            blur: self.blur.to_bits(),
+        }
+    }
+
+    pub fn variation(&self) -> Variation {
+        Variation {
+            weight: self.thickness.weight() as f32,
+            width: self.stretch,
+        }
     }
 }
 
@@ -32,3 +92,144 @@ pub(crate) struct TextRenderingData {
     pub glyphs: Vec<Glyph>,
     pub metrics: Vec<Metrics>,
 }
+
+// Mandatory breaks under UAX #14
+pub(crate) fn is_newline(character: char) -> bool {
+    matches!(
+        character,
+        '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
+
+// Invisible unless a line breaks after it, where it's drawn as a hyphen
+pub(crate) const SOFT_HYPHEN: char = '\u{AD}';
+
+// Zero-width formatting characters that many fonts leave out entirely
+pub(crate) fn is_invisible(character: char) -> bool {
+    matches!(
+        character,
+        SOFT_HYPHEN | '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}'
+    )
+}
+
+// Dilates coverage by outline pixels, then applies a gaussian blur with a standard deviation of
+// half the blur radius, padding the bitmap to fit
+pub(crate) fn spread(
+    metrics: Metrics,
+    bitmap: Vec<u8>,
+    outline: f32,
+    blur: f32,
+) -> (Metrics, Vec<u8>) {
+    if (outline <= 0.0 && blur <= 0.0) || metrics.width == 0 || metrics.height == 0 {
+        return (metrics, bitmap);
+    }
+
+    let reach = if outline > 0.0 {
+        outline.ceil() as usize + 1
+    } else {
+        0
+    };
+    let radius = (1.5 * blur).ceil() as usize;
+    let pad = reach + radius;
+
+    let width = metrics.width + 2 * pad;
+    let height = metrics.height + 2 * pad;
+
+    let mut coverage = vec![0.0; width * height];
+    for y in 0..metrics.height {
+        for x in 0..metrics.width {
+            coverage[x + pad + (y + pad) * width] = bitmap[x + y * metrics.width] as f32;
+        }
+    }
+
+    if outline > 0.0 {
+        coverage = dilate(&coverage, width, height, outline, reach);
+    }
+    if radius > 0 {
+        coverage = gaussian(&coverage, width, height, 0.5 * blur, radius);
+    }
+
+    let mut metrics = metrics;
+    metrics.width = width;
+    metrics.height = height;
+    metrics.xmin -= pad as i32;
+    metrics.ymin -= pad as i32;
+
+    let bitmap = coverage
+        .iter()
+        .map(|value| f32::min(value.round(), 255.0) as u8)
+        .collect();
+
+    (metrics, bitmap)
+}
+
+// Strongest coverage within a disc, fading over the disc's last pixel to stay antialiased
+fn dilate(source: &[f32], width: usize, height: usize, outline: f32, reach: usize) -> Vec<f32> {
+    let reach = reach as isize;
+    let mut dilated = vec![0.0; source.len()];
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let mut strongest: f32 = 0.0;
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let (sx, sy) = (x + dx, y + dy);
+                    if sx < 0 || sy < 0 || sx >= width as isize || sy >= height as isize {
+                        continue;
+                    }
+
+                    let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                    let weight = (outline + 0.5 - distance).clamp(0.0, 1.0);
+                    strongest = strongest.max(source[(sx + sy * width as isize) as usize] * weight);
+                }
+            }
+            dilated[(x + y * width as isize) as usize] = strongest;
+        }
+    }
+
+    dilated
+}
+
+// Separable, so it blurs rows and then columns
+fn gaussian(source: &[f32], width: usize, height: usize, sigma: f32, radius: usize) -> Vec<f32> {
+    let kernel: Vec<f32> = (0..=2 * radius)
+        .map(|i| {
+            let offset = i as f32 - radius as f32;
+            (-offset * offset / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let total: f32 = kernel.iter().sum();
+
+    let sample = |values: &[f32], x: isize, y: isize| {
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            0.0
+        } else {
+            values[x as usize + y as usize * width]
+        }
+    };
+
+    let mut rows = vec![0.0; source.len()];
+    let mut columns = vec![0.0; source.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let mut horizontal = 0.0;
+            for (k, weight) in kernel.iter().enumerate() {
+                let offset = k as isize - radius as isize;
+                horizontal += sample(source, x as isize + offset, y as isize) * weight;
+            }
+            rows[x + y * width] = horizontal / total;
+        }
+    }
+    for y in 0..height {
+        for x in 0..width {
+            let mut vertical = 0.0;
+            for (k, weight) in kernel.iter().enumerate() {
+                let offset = k as isize - radius as isize;
+                vertical += sample(&rows, x as isize, y as isize + offset) * weight;
+            }
+            columns[x + y * width] = vertical / total;
+        }
+    }
+
+    columns
+}
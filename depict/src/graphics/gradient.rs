@@ -0,0 +1,139 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use crate::{component::memory::Memory, core::properties::Background};
+
+use super::{asset::Assets, atlas::Atlas, color::Color, image::Image};
+
+// Texels in a ramp, which the shader relies on to find the neighbours it blends between
+pub(crate) const RAMP_WIDTH: u32 = 256;
+
+// Ramps packed into their own atlas, found again by their stops so drawing doesn't rebuild them
+#[derive(Debug)]
+pub(crate) struct GradientAsset {
+    pub(crate) atlas: Atlas,
+    ramps: HashMap<Vec<u32>, Memory<u32>>,
+}
+
+impl GradientAsset {
+    pub(crate) fn new(atlas: Atlas) -> Self {
+        Self {
+            atlas,
+            ramps: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn request(&mut self, stops: &[(f32, Color)]) {
+        let atlas = &mut self.atlas;
+        let ramp = self
+            .ramps
+            .entry(key(stops))
+            .or_insert_with(|| Memory::new(atlas.add(ramp(stops))));
+        ramp.remembered = true;
+    }
+
+    // Where the ramp for the stops was packed, once it's been requested and the atlas updated
+    pub(crate) fn get(&self, stops: &[(f32, Color)]) -> Option<&Image> {
+        let id = self.ramps.get(&key(stops))?;
+        self.atlas.images.get(&id.value)
+    }
+
+    // Drops the ramps that weren't requested since the last sweep. Stops that bake into the same
+    // ramp share its texels, so those stay for as long as any of them is still around.
+    pub(crate) fn sweep(&mut self) {
+        self.ramps.retain(|_, ramp| mem::take(&mut ramp.remembered));
+
+        let kept: HashSet<u32> = self.ramps.values().map(|ramp| ramp.value).collect();
+        let unused: Vec<_> = self
+            .atlas
+            .sources
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !kept.contains(id))
+            .collect();
+        for id in unused {
+            self.atlas.remove(id);
+        }
+    }
+}
+
+fn key(stops: &[(f32, Color)]) -> Vec<u32> {
+    stops
+        .iter()
+        .flat_map(|(position, color)| {
+            [
+                position,
+                &color.red,
+                &color.green,
+                &color.blue,
+                &color.alpha,
+            ]
+            .map(|value| value.to_bits())
+        })
+        .collect()
+}
+
+// Asks for the ramp a gradient background samples from, so it's packed into the atlas by render
+pub(crate) fn request(background: &Background, assets: &mut Assets) {
+    if let Some(stops) = background.stops() {
+        if !stops.is_empty() {
+            assets.gradients.request(stops);
+        }
+    }
+}
+
+// Colors along the gradient baked into a strip, with the first and last texels exactly at the
+// ends. The atlas is sRGB, so they're encoded to sample back as the stops' values.
+fn ramp(stops: &[(f32, Color)]) -> DynamicImage {
+    let mut stops = stops.to_vec();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut image = RgbaImage::new(RAMP_WIDTH, 1);
+    for (x, _, pixel) in image.enumerate_pixels_mut() {
+        let color = sample(&stops, x as f32 / (RAMP_WIDTH - 1) as f32);
+        *pixel = Rgba([
+            encode(color.red),
+            encode(color.green),
+            encode(color.blue),
+            (color.alpha.clamp(0.0, 1.0) * 255.0).round() as u8,
+        ]);
+    }
+
+    DynamicImage::ImageRgba8(image)
+}
+
+fn sample(stops: &[(f32, Color)], position: f32) -> Color {
+    let after = stops.partition_point(|(stop, _)| *stop <= position);
+    if after == 0 {
+        return stops[0].1;
+    }
+    if after == stops.len() {
+        return stops[after - 1].1;
+    }
+
+    let (start, from) = stops[after - 1];
+    let (end, to) = stops[after];
+    let t = (position - start) / (end - start);
+
+    Color::new(
+        from.red + (to.red - from.red) * t,
+        from.green + (to.green - from.green) * t,
+        from.blue + (to.blue - from.blue) * t,
+        from.alpha + (to.alpha - from.alpha) * t,
+    )
+}
+
+fn encode(component: f32) -> u8 {
+    let component = component.clamp(0.0, 1.0);
+    let encoded = if component <= 0.0031308 {
+        component * 12.92
+    } else {
+        1.055 * component.powf(1.0 / 2.4) - 0.055
+    };
+
+    (encoded * 255.0).round() as u8
+}
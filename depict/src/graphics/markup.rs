@@ -0,0 +1,232 @@
+use std::{error::Error, fmt::Display};
+
+use crate::builtin::text::{TextSpan, TextStyle};
+
+use super::{
+    color::Color,
+    font::{FontEmphasis, FontThickness},
+};
+
+// Positions are byte offsets into the markup source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkupError {
+    UnclosedBracket(usize),
+    UnknownTag(String, usize),
+    InvalidValue(String, usize),
+    MismatchedClose(String, usize),
+    Unclosed(String, usize),
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarkupError::UnclosedBracket(pos) => write!(f, "Tag at {} is missing a ']'", pos),
+            MarkupError::UnknownTag(tag, pos) => write!(f, "Unknown tag [{}] at {}", tag, pos),
+            MarkupError::InvalidValue(tag, pos) => {
+                write!(f, "Invalid value in [{}] at {}", tag, pos)
+            }
+            MarkupError::MismatchedClose(tag, pos) => {
+                write!(f, "[/{}] at {} doesn't close the innermost tag", tag, pos)
+            }
+            MarkupError::Unclosed(tag, pos) => write!(f, "[{}] at {} is never closed", tag, pos),
+        }
+    }
+}
+
+impl Error for MarkupError {}
+
+struct Open {
+    name: String,
+    style: TextStyle,
+    start: usize,
+    position: usize,
+    order: usize,
+}
+
+// Parses BBCode-like markup into plain text and the spans styling it:
+// [b], [i], [u], [s], [color=#rrggbb], [color=#rrggbbaa], [color=red] and [size=24], each closed by [/tag].
+// A literal '[' is written as "[[".
+pub fn parse(markup: &str) -> Result<(String, Vec<TextSpan>), MarkupError> {
+    let mut text = String::new();
+    let mut length = 0;
+    let mut stack: Vec<Open> = Vec::new();
+    let mut spans = Vec::new();
+    let mut order = 0;
+
+    let mut rest = markup;
+    while let Some(bracket) = rest.find('[') {
+        let position = markup.len() - rest.len() + bracket;
+
+        text.push_str(&rest[..bracket]);
+        length += rest[..bracket].chars().count();
+        rest = &rest[bracket + 1..];
+
+        if let Some(escaped) = rest.strip_prefix('[') {
+            text.push('[');
+            length += 1;
+            rest = escaped;
+            continue;
+        }
+
+        let end = rest
+            .find(']')
+            .ok_or(MarkupError::UnclosedBracket(position))?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let open = stack
+                .pop()
+                .filter(|open| open.name == name)
+                .ok_or(MarkupError::MismatchedClose(name.to_string(), position))?;
+
+            if open.start < length {
+                spans.push((
+                    open.order,
+                    TextSpan {
+                        range: open.start..length,
+                        style: open.style,
+                    },
+                ));
+            }
+            continue;
+        }
+
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (tag, None),
+        };
+
+        stack.push(Open {
+            name: name.to_string(),
+            style: style(name, value, position)?,
+            start: length,
+            position,
+            order,
+        });
+        order += 1;
+    }
+    text.push_str(rest);
+
+    if let Some(open) = stack.pop() {
+        return Err(MarkupError::Unclosed(open.name, open.position));
+    }
+
+    // Outer tags open first, so applying spans in opening order lets inner tags win
+    spans.sort_by_key(|(order, _)| *order);
+
+    Ok((text, spans.into_iter().map(|(_, span)| span).collect()))
+}
+
+fn style(name: &str, value: Option<&str>, position: usize) -> Result<TextStyle, MarkupError> {
+    let invalid = || MarkupError::InvalidValue(name.to_string(), position);
+
+    match (name, value) {
+        ("b", None) => Ok(TextStyle::new().with_thickness(FontThickness::Bold)),
+        ("i", None) => Ok(TextStyle::new().with_emphasis(FontEmphasis::Italic)),
+        ("u", None) => Ok(TextStyle::new().with_underline(true)),
+        ("s", None) => Ok(TextStyle::new().with_strikethrough(true)),
+        ("color", Some(value)) => color(value)
+            .map(|color| TextStyle::new().with_color(color))
+            .ok_or_else(invalid),
+        ("size", Some(value)) => value
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|size| *size > 0.0 && size.is_finite())
+            .map(|size| TextStyle::new().with_size(size))
+            .ok_or_else(invalid),
+        ("b" | "i" | "u" | "s", Some(_)) | ("color" | "size", None) => Err(invalid()),
+        _ => Err(MarkupError::UnknownTag(name.to_string(), position)),
+    }
+}
+
+fn color(value: &str) -> Option<Color> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let parsed = u32::from_str_radix(hex, 16).ok()?;
+        return match hex.len() {
+            6 => Some(Color::from_hex(parsed << 8 | 0xff)),
+            8 => Some(Color::from_hex(parsed)),
+            _ => None,
+        };
+    }
+
+    match value.to_lowercase().as_str() {
+        "red" => Some(Color::RED),
+        "green" => Some(Color::GREEN),
+        "blue" => Some(Color::BLUE),
+        "cyan" => Some(Color::CYAN),
+        "magenta" => Some(Color::MAGENTA),
+        "yellow" => Some(Color::YELLOW),
+        "black" => Some(Color::BLACK),
+        "white" => Some(Color::WHITE),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_tags() {
+        let (text, spans) = parse("a[b]b[i]c[/i][/b]").unwrap();
+
+        assert_eq!(text, "abc");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].range, 1..3);
+        assert_eq!(spans[0].style.thickness, Some(FontThickness::Bold));
+        assert_eq!(spans[1].range, 2..3);
+        assert_eq!(spans[1].style.emphasis, Some(FontEmphasis::Italic));
+    }
+
+    #[test]
+    fn escaped_bracket() {
+        let (text, spans) = parse("[[b] and [u]x[/u]").unwrap();
+
+        assert_eq!(text, "[b] and x");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].range, 8..9);
+    }
+
+    #[test]
+    fn multibyte_ranges() {
+        let (text, spans) = parse("héllo [color=red]wörld[/color] ✓[s]✓[/s]").unwrap();
+
+        assert_eq!(text, "héllo wörld ✓✓");
+        assert_eq!(spans[0].range, 6..11);
+        assert_eq!(spans[0].style.color, Some(Color::RED));
+        assert_eq!(spans[1].range, 13..14);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("ab[b]c"),
+            Err(MarkupError::Unclosed("b".to_string(), 2))
+        );
+        assert_eq!(
+            parse("[b][i]x[/b][/i]"),
+            Err(MarkupError::MismatchedClose("b".to_string(), 7))
+        );
+        assert_eq!(parse("é[b"), Err(MarkupError::UnclosedBracket(2)));
+        assert_eq!(
+            parse("[size=-4]x[/size]"),
+            Err(MarkupError::InvalidValue("size".to_string(), 0))
+        );
+        assert_eq!(
+            parse("x[color=#12345]y[/color]"),
+            Err(MarkupError::InvalidValue("color".to_string(), 1))
+        );
+        assert_eq!(
+            parse("[wave]x[/wave]"),
+            Err(MarkupError::UnknownTag("wave".to_string(), 0))
+        );
+    }
+}
@@ -0,0 +1,1193 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+    f32::consts::{PI, TAU},
+    iter, mem,
+};
+
+use glam::Vec2;
+
+use crate::{
+    component::{bounds::BoundingBox, memory::Memory},
+    core::properties::{Background, Border, FillRule, LineCap, LineJoin, Stroke},
+    engine::{renderer::RenderBatch, shader::Vertex},
+};
+
+use super::color::Color;
+
+const EPSILON: f32 = 1e-4;
+
+// How far flattened curves may stray from the real ones, in physical pixels
+pub(crate) const TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Copy, Clone)]
+struct Edge {
+    top: Vec2,
+    bottom: Vec2,
+    // +1 when the contour runs down the edge, -1 when it runs up
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let t = (y - self.top.y) / (self.bottom.y - self.top.y);
+        self.top.x + (self.bottom.x - self.top.x) * t
+    }
+
+    fn slope(&self) -> f32 {
+        (self.bottom.x - self.top.x) / (self.bottom.y - self.top.y)
+    }
+}
+
+// Triangulates closed contours with a sweep from top to bottom, keeping the edges it crosses sorted
+// by x. Walking them left to right while counting windings gives the filled spans, whatever the
+// fill rule, holes or self-intersections. A span comes out as one trapezoid for as long as the same
+// pair of edges bounds it, and only neighbouring edges are checked for crossings, since those are
+// the only ones that can swap next.
+pub(crate) fn fill(contours: &[Vec<Vec2>], rule: FillRule) -> Vec<[Vec2; 3]> {
+    let mut edges = Vec::new();
+    for contour in contours {
+        for (idx, &start) in contour.iter().enumerate() {
+            let end = contour[(idx + 1) % contour.len()];
+            if (start.y - end.y).abs() < EPSILON {
+                continue;
+            }
+
+            edges.push(if start.y < end.y {
+                Edge {
+                    top: start,
+                    bottom: end,
+                    winding: 1,
+                }
+            } else {
+                Edge {
+                    top: end,
+                    bottom: start,
+                    winding: -1,
+                }
+            });
+        }
+    }
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+
+    let mut events: Vec<f32> = edges
+        .iter()
+        .flat_map(|edge| [edge.top.y, edge.bottom.y])
+        .collect();
+    events.sort_by(f32::total_cmp);
+
+    let mut crossings = BinaryHeap::new();
+    let mut checked = HashSet::new();
+
+    let mut triangles = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    let mut spans: HashMap<(usize, usize), f32> = HashMap::new();
+    let (mut next_edge, mut next_event) = (0, 0);
+    let mut y = f32::NEG_INFINITY;
+    loop {
+        while events
+            .get(next_event)
+            .is_some_and(|&event| event <= y + EPSILON)
+        {
+            next_event += 1;
+        }
+        while crossings
+            .peek()
+            .is_some_and(|Reverse(Height(crossing))| *crossing <= y + EPSILON)
+        {
+            crossings.pop();
+        }
+
+        let upcoming = [
+            events.get(next_event).copied(),
+            crossings.peek().map(|Reverse(Height(crossing))| *crossing),
+        ];
+        let Some(next) = upcoming.into_iter().flatten().min_by(f32::total_cmp) else {
+            break;
+        };
+        y = next;
+
+        active.retain(|&idx| edges[idx].bottom.y > y + EPSILON);
+        while edges
+            .get(next_edge)
+            .is_some_and(|edge| edge.top.y <= y + EPSILON)
+        {
+            if edges[next_edge].bottom.y > y + EPSILON {
+                active.push(next_edge);
+            }
+            next_edge += 1;
+        }
+
+        // Nearly sorted from the last event, apart from new edges and ones that just crossed
+        let before = |a: usize, b: usize| {
+            let (a, b) = (&edges[a], &edges[b]);
+            let (a_x, b_x) = (a.x_at(y), b.x_at(y));
+            if (a_x - b_x).abs() > EPSILON {
+                a_x < b_x
+            } else {
+                a.slope() < b.slope()
+            }
+        };
+        for idx in 1..active.len() {
+            let mut at = idx;
+            while at > 0 && before(active[at], active[at - 1]) {
+                active.swap(at, at - 1);
+                at -= 1;
+            }
+        }
+
+        // Two edges cross at most once, so each pair only needs checking the first time they meet
+        for pair in active.windows(2) {
+            let key = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+            if !checked.insert(key) {
+                continue;
+            }
+
+            if let Some(crossing) = crossing(&edges[pair[0]], &edges[pair[1]]) {
+                if crossing > y + EPSILON {
+                    crossings.push(Reverse(Height(crossing)));
+                }
+            }
+        }
+
+        let mut current = HashSet::new();
+        let mut winding = 0;
+        let mut left = None;
+        for &idx in &active {
+            let was_inside = rule.inside(winding);
+            winding += edges[idx].winding;
+
+            match (was_inside, rule.inside(winding)) {
+                (false, true) => left = Some(idx),
+                (true, false) => {
+                    if let Some(left) = left.take() {
+                        current.insert((left, idx));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Spans no longer bounded by the same pair of edges end here
+        spans.retain(|&(left, right), top| {
+            let open = current.contains(&(left, right));
+            if !open {
+                trapezoid(&edges[left], &edges[right], *top, y, &mut triangles);
+            }
+            open
+        });
+        for span in current {
+            spans.entry(span).or_insert(y);
+        }
+    }
+
+    triangles
+}
+
+fn trapezoid(left: &Edge, right: &Edge, top: f32, bottom: f32, triangles: &mut Vec<[Vec2; 3]>) {
+    let top_left = Vec2::new(left.x_at(top), top);
+    let top_right = Vec2::new(right.x_at(top), top);
+    let bottom_left = Vec2::new(left.x_at(bottom), bottom);
+    let bottom_right = Vec2::new(right.x_at(bottom), bottom);
+
+    if top_right.x - top_left.x > EPSILON {
+        triangles.push([top_left, top_right, bottom_left]);
+    }
+    if bottom_right.x - bottom_left.x > EPSILON {
+        triangles.push([top_right, bottom_right, bottom_left]);
+    }
+}
+
+// Orders the heap of upcoming crossings
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Height(f32);
+
+impl Eq for Height {}
+
+impl PartialOrd for Height {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Height {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// Every contour's length followed by its points' bits, and the rule they're filled with
+type FillKey = (Vec<u32>, FillRule);
+
+// Triangulations of the contours drawn lately, since most shapes look the same from frame to frame
+#[derive(Debug, Default)]
+pub(crate) struct FillCache {
+    fills: HashMap<FillKey, Memory<Vec<[Vec2; 3]>>>,
+}
+
+impl FillCache {
+    pub(crate) fn fill(&mut self, contours: &[Vec<Vec2>], rule: FillRule) -> &[[Vec2; 3]] {
+        let key = contours
+            .iter()
+            .flat_map(|contour| {
+                iter::once(contour.len() as u32).chain(
+                    contour
+                        .iter()
+                        .flat_map(|point| [point.x.to_bits(), point.y.to_bits()]),
+                )
+            })
+            .collect();
+
+        let triangles = self
+            .fills
+            .entry((key, rule))
+            .or_insert_with(|| Memory::new(fill(contours, rule)));
+        triangles.remembered = true;
+        &triangles.value
+    }
+
+    // Forgets the ones that weren't drawn since the last sweep
+    pub(crate) fn sweep(&mut self) {
+        self.fills
+            .retain(|_, triangles| mem::take(&mut triangles.remembered));
+    }
+}
+
+// Height where two edges properly cross, if they do
+fn crossing(a: &Edge, b: &Edge) -> Option<f32> {
+    let r = a.bottom - a.top;
+    let s = b.bottom - b.top;
+    let denominator = r.perp_dot(s);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let offset = b.top - a.top;
+    let t = offset.perp_dot(s) / denominator;
+    let u = offset.perp_dot(r) / denominator;
+    if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+        Some(a.top.y + r.y * t)
+    } else {
+        None
+    }
+}
+
+// Flattening appends the points after the start of each curve, spaced so the chords stay within
+// tolerance of it
+pub(crate) fn quad(from: Vec2, control: Vec2, to: Vec2, tolerance: f32, points: &mut Vec<Vec2>) {
+    let deviation = (from - 2.0 * control + to).length();
+    let steps = (deviation / (4.0 * tolerance))
+        .sqrt()
+        .ceil()
+        .clamp(1.0, 256.0) as usize;
+
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        let u = 1.0 - t;
+        points.push(from * u * u + control * 2.0 * u * t + to * t * t);
+    }
+}
+
+pub(crate) fn cubic(
+    from: Vec2,
+    first: Vec2,
+    second: Vec2,
+    to: Vec2,
+    tolerance: f32,
+    points: &mut Vec<Vec2>,
+) {
+    let deviation = f32::max(
+        (from - 2.0 * first + second).length(),
+        (first - 2.0 * second + to).length(),
+    );
+    let steps = (3.0 * deviation / (4.0 * tolerance))
+        .sqrt()
+        .ceil()
+        .clamp(1.0, 256.0) as usize;
+
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        let u = 1.0 - t;
+        points.push(
+            from * u * u * u + first * 3.0 * u * u * t + second * 3.0 * u * t * t + to * t * t * t,
+        );
+    }
+}
+
+// Angles start at +x and grow clockwise on screen, like in Circle
+pub(crate) fn arc(
+    center: Vec2,
+    radius: f32,
+    start: f32,
+    sweep: f32,
+    tolerance: f32,
+    points: &mut Vec<Vec2>,
+) {
+    let step = 2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos();
+    let steps = (sweep.abs() / step.max(EPSILON)).ceil().clamp(1.0, 1024.0) as usize;
+
+    for i in 1..=steps {
+        let angle = start + sweep * i as f32 / steps as f32;
+        points.push(center + Vec2::new(angle.cos(), angle.sin()) * radius);
+    }
+}
+
+// Strokes thinner than the feather are drawn at its width instead, faded to keep the same weight,
+// since the fringe alone would make them look heavier
+pub(crate) fn hairline(width: f32, color: Color, feather: f32) -> (f32, Color) {
+    if width <= 0.0 || width >= feather {
+        return (width, color);
+    }
+
+    let fade = width / feather;
+    let color = Color::new(
+        color.red * fade,
+        color.green * fade,
+        color.blue * fade,
+        color.alpha * fade,
+    );
+    (feather, color)
+}
+
+// Triangles covering polylines drawn with the stroke, along with how much of it covers each point.
+// Every polyline is a ribbon of cross-sections at its points, which share their inner side at joins
+// so nothing overlaps, and a positive feather fades only its outer edges out.
+pub(crate) fn stroke(
+    polylines: &[(Vec<Vec2>, bool)],
+    stroke: &Stroke,
+    tolerance: f32,
+    feather: f32,
+) -> Vec<[(Vec2, f32); 3]> {
+    let half = stroke.width / 2.0;
+    if half <= 0.0 {
+        return Vec::new();
+    }
+
+    let dashed = stroke.dashes.iter().all(|length| *length >= 0.0)
+        && stroke.dashes.iter().sum::<f32>() > 0.0;
+
+    let mut ribbon = Ribbon {
+        stroke,
+        core: f32::max(half - feather / 2.0, 0.0),
+        edge: half + feather / 2.0,
+        tolerance,
+        triangles: Vec::new(),
+    };
+    for (points, closed) in polylines {
+        let mut points = points.clone();
+        points.dedup_by(|a, b| a.distance(*b) < EPSILON);
+        if *closed && points.len() > 1 && points[0].distance(points[points.len() - 1]) < EPSILON {
+            points.pop();
+        }
+
+        if dashed {
+            for dash in dashes(&points, *closed, &stroke.dashes, stroke.dash_offset) {
+                ribbon.outline(&dash, false);
+            }
+        } else {
+            ribbon.outline(&points, *closed);
+        }
+    }
+
+    ribbon.triangles
+}
+
+// Points across a stroke from the outside of its fringe on the left of the way it goes to the
+// outside on the right, with how much of it covers each
+type Section = [(Vec2, f32); 4];
+
+struct Ribbon<'a> {
+    stroke: &'a Stroke,
+    // Distances from the middle to where the stroke starts fading, and to where it's gone
+    core: f32,
+    edge: f32,
+    tolerance: f32,
+    triangles: Vec<[(Vec2, f32); 3]>,
+}
+
+impl Ribbon<'_> {
+    fn outline(&mut self, points: &[Vec2], closed: bool) {
+        let mut points = points.to_vec();
+        points.dedup_by(|a, b| a.distance(*b) < EPSILON);
+
+        let count = points.len();
+        match count {
+            0 => return,
+            1 => return self.dot(points[0]),
+            _ => {}
+        }
+
+        if closed {
+            let (first, mut previous) = self.join(points[count - 1], points[0], points[1]);
+            for idx in 1..count {
+                let (arriving, leaving) =
+                    self.join(points[idx - 1], points[idx], points[(idx + 1) % count]);
+                self.band(&previous, &arriving);
+                previous = leaving;
+            }
+            self.band(&previous, &first);
+            return;
+        }
+
+        let start = (points[1] - points[0]).normalize();
+        let end = (points[count - 1] - points[count - 2]).normalize();
+
+        let mut previous = self.cap(points[0], start, true, self.stroke.cap);
+        for idx in 1..count - 1 {
+            let (arriving, leaving) = self.join(points[idx - 1], points[idx], points[idx + 1]);
+            self.band(&previous, &arriving);
+            previous = leaving;
+        }
+        let last = self.cap(points[count - 1], end, false, self.stroke.cap);
+        self.band(&previous, &last);
+    }
+
+    // Zero length lines still show their caps, as dots or squares
+    fn dot(&mut self, point: Vec2) {
+        match self.stroke.cap {
+            LineCap::Butt => {}
+            LineCap::Round => self.round(point, point, Vec2::X, TAU),
+            LineCap::Square => {
+                let reach = Vec2::X * (self.core + self.edge) / 2.0;
+                let start = self.cap(point - reach, Vec2::X, true, LineCap::Butt);
+                let end = self.cap(point + reach, Vec2::X, false, LineCap::Butt);
+                self.band(&start, &end);
+            }
+        }
+    }
+
+    fn section(&self, point: Vec2, normal: Vec2) -> Section {
+        [
+            (point + normal * self.edge, 0.0),
+            (point + normal * self.core, 1.0),
+            (point - normal * self.core, 1.0),
+            (point - normal * self.edge, 0.0),
+        ]
+    }
+
+    // Draws the cap at an end of the line going in direction, returning the section the rest of
+    // the line starts or finishes at
+    fn cap(&mut self, point: Vec2, direction: Vec2, start: bool, cap: LineCap) -> Section {
+        let normal = direction.perp();
+        let outward = if start { -direction } else { direction };
+
+        if cap == LineCap::Round {
+            let sweep = if start { PI } else { -PI };
+            self.round(point, point, normal, sweep);
+            return self.section(point, normal);
+        }
+
+        let reach = if cap == LineCap::Square {
+            (self.core + self.edge) / 2.0
+        } else {
+            0.0
+        };
+        let tip = point + outward * reach;
+        if self.edge <= self.core {
+            return self.section(tip, normal);
+        }
+
+        // Ends fade out across the feather too, centered on where they'd be
+        let fade = (self.edge - self.core) / 2.0;
+        let section = self.section(tip - outward * fade, normal);
+        let faded = self
+            .section(tip + outward * fade, normal)
+            .map(|(point, _)| (point, 0.0));
+        if start {
+            self.band(&faded, &section);
+        } else {
+            self.band(&section, &faded);
+        }
+        section
+    }
+
+    // Sections arriving at and leaving a corner, filling the wedge left open on its outside
+    fn join(&mut self, before: Vec2, point: Vec2, after: Vec2) -> (Section, Section) {
+        let (incoming, outgoing) = ((point - before).normalize(), (after - point).normalize());
+        let (normal_in, normal_out) = (incoming.perp(), outgoing.perp());
+
+        let turn = incoming.perp_dot(outgoing);
+        if turn.abs() < EPSILON && incoming.dot(outgoing) > 0.0 {
+            let section = self.section(point, normal_in);
+            return (section, section);
+        }
+
+        // The line turns towards its inner side, where the normals point when it's positive
+        let side = if turn > 0.0 { 1.0 } else { -1.0 };
+        let middle = (normal_in + normal_out).normalize_or_zero();
+        let cos = middle.dot(normal_in);
+
+        // Inner edges meet at a point, kept from reaching past the neighbouring points
+        let room = f32::min(point.distance(before), point.distance(after));
+        let inner = |offset: f32| {
+            if cos <= EPSILON {
+                return point;
+            }
+            let reach = f32::min(offset / cos, (offset * offset + room * room).sqrt());
+            point + middle * side * reach
+        };
+        let across = |outer: &dyn Fn(f32) -> Vec2| -> Section {
+            let mut section = [
+                (inner(self.edge), 0.0),
+                (inner(self.core), 1.0),
+                (outer(self.core), 1.0),
+                (outer(self.edge), 0.0),
+            ];
+            if side < 0.0 {
+                section.reverse();
+            }
+            section
+        };
+
+        let miter = cos > EPSILON && 1.0 / cos <= self.stroke.miter_limit;
+        if self.stroke.join == LineJoin::Miter && miter {
+            let section = across(&|offset| point - middle * side * offset / cos);
+            return (section, section);
+        }
+
+        let outer_in = move |offset: f32| point - normal_in * side * offset;
+        let outer_out = move |offset: f32| point - normal_out * side * offset;
+        let (arriving, leaving) = (across(&outer_in), across(&outer_out));
+
+        if self.stroke.join == LineJoin::Round {
+            let (from, to) = (-normal_in * side, -normal_out * side);
+            let sweep = from.perp_dot(to).atan2(from.dot(to));
+            self.round(inner(self.core), point, from, sweep);
+        } else {
+            let (core_in, core_out) = (outer_in(self.core), outer_out(self.core));
+            self.triangles
+                .push([(inner(self.core), 1.0), (core_in, 1.0), (core_out, 1.0)]);
+            if self.edge > self.core {
+                self.quad(
+                    (core_in, 1.0),
+                    (outer_in(self.edge), 0.0),
+                    (outer_out(self.edge), 0.0),
+                    (core_out, 1.0),
+                );
+            }
+        }
+
+        (arriving, leaving)
+    }
+
+    // Fans out from apex to an arc around center, starting in direction from, with its fringe
+    fn round(&mut self, apex: Vec2, center: Vec2, from: Vec2, sweep: f32) {
+        let mut directions = vec![from];
+        arc(
+            Vec2::ZERO,
+            1.0,
+            from.y.atan2(from.x),
+            sweep,
+            self.tolerance / self.edge,
+            &mut directions,
+        );
+
+        for pair in directions.windows(2) {
+            let (core_a, core_b) = (center + pair[0] * self.core, center + pair[1] * self.core);
+            self.triangles
+                .push([(apex, 1.0), (core_a, 1.0), (core_b, 1.0)]);
+            if self.edge > self.core {
+                self.quad(
+                    (core_a, 1.0),
+                    (center + pair[0] * self.edge, 0.0),
+                    (center + pair[1] * self.edge, 0.0),
+                    (core_b, 1.0),
+                );
+            }
+        }
+    }
+
+    // Joins two sections, skipping the fringes when there's no feather
+    fn band(&mut self, from: &Section, to: &Section) {
+        for idx in 0..3 {
+            if idx != 1 && self.edge <= self.core {
+                continue;
+            }
+            self.quad(from[idx], from[idx + 1], to[idx + 1], to[idx]);
+        }
+    }
+
+    fn quad(&mut self, a: (Vec2, f32), b: (Vec2, f32), c: (Vec2, f32), d: (Vec2, f32)) {
+        self.triangles.push([a, b, c]);
+        self.triangles.push([a, c, d]);
+    }
+}
+
+// Splits a polyline into the drawn parts of a dash pattern, which alternates drawn and skipped
+// lengths and starts offset into itself. Strokes with negative lengths or a pattern adding up to
+// nothing stay solid instead.
+fn dashes(points: &[Vec2], closed: bool, pattern: &[f32], offset: f32) -> Vec<Vec<Vec2>> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let mut points = points.to_vec();
+    if closed {
+        points.push(points[0]);
+    }
+
+    // Odd patterns repeat to make an even one, as in SVG
+    let pattern = if pattern.len() % 2 == 1 {
+        pattern.repeat(2)
+    } else {
+        pattern.to_vec()
+    };
+
+    let total: f32 = pattern.iter().sum();
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut skipped = offset.rem_euclid(total);
+    while skipped >= remaining {
+        skipped -= remaining;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    remaining -= skipped;
+
+    let mut drawing = index % 2 == 0;
+    let mut dashes = Vec::new();
+    let mut current = if drawing { vec![points[0]] } else { Vec::new() };
+
+    for segment in points.windows(2) {
+        let (mut start, end) = (segment[0], segment[1]);
+        let mut length = start.distance(end);
+
+        while length > remaining {
+            let split = start + (end - start) * (remaining / length);
+            if drawing {
+                current.push(split);
+                dashes.push(mem::take(&mut current));
+            } else {
+                current = vec![split];
+            }
+
+            drawing = !drawing;
+            length -= remaining;
+            start = split;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+
+        remaining -= length;
+        if drawing {
+            current.push(end);
+        }
+    }
+
+    if drawing && current.len() > 1 {
+        dashes.push(current);
+    }
+
+    dashes
+}
+
+// Ring covering a closed contour's border. Contours wound with a positive area have their outside
+// outward, so holes wound the other way get theirs inside the hole.
+pub(crate) fn border(contour: &[Vec2], border: Border) -> Vec<Vec<Vec2>> {
+    if border.thickness <= 0.0 || contour.len() < 3 {
+        return Vec::new();
+    }
+
+    let (inner, outer) = border.extent();
+    ring(offset(contour, outer), offset(contour, inner))
+}
+
+// Contours for the area between two wound the same way, with the inner one inside the outer, to
+// fill with the nonzero rule
+pub(crate) fn ring(outer: Vec<Vec2>, mut inner: Vec<Vec2>) -> Vec<Vec<Vec2>> {
+    inner.reverse();
+    vec![outer, inner]
+}
+
+// Rectangle outline wound with a positive area, with corners rounded by the radii in order from
+// the top left. Radii should already fit.
+pub(crate) fn rounded(bounds: BoundingBox, radii: [f32; 4], tolerance: f32) -> Vec<Vec2> {
+    let BoundingBox {
+        x,
+        y,
+        width,
+        height,
+    } = bounds;
+    let corners = [
+        (Vec2::new(x, y), Vec2::new(1.0, 1.0), PI),
+        (Vec2::new(x + width, y), Vec2::new(-1.0, 1.0), PI * 1.5),
+        (Vec2::new(x + width, y + height), Vec2::new(-1.0, -1.0), 0.0),
+        (Vec2::new(x, y + height), Vec2::new(1.0, -1.0), PI * 0.5),
+    ];
+
+    let mut points = Vec::new();
+    for ((corner, inward, start), radius) in corners.into_iter().zip(radii) {
+        if radius <= 0.0 {
+            points.push(corner);
+            continue;
+        }
+
+        let center = corner + inward * radius;
+        points.push(center + Vec2::new(start.cos(), start.sin()) * radius);
+        arc(center, radius, start, PI / 2.0, tolerance, &mut points);
+    }
+
+    points.dedup_by(|a, b| a.distance(*b) < EPSILON);
+    if points.len() > 1 && points[0].distance(points[points.len() - 1]) < EPSILON {
+        points.pop();
+    }
+    points
+}
+
+// Moves every edge of a contour outward by the distance, or inward when it's negative, with
+// mitered corners limited so sharp ones don't spike
+pub(crate) fn offset(contour: &[Vec2], distance: f32) -> Vec<Vec2> {
+    const LIMIT: f32 = 4.0;
+
+    if distance == 0.0 {
+        return contour.to_vec();
+    }
+
+    let count = contour.len();
+    (0..count)
+        .map(|idx| {
+            let point = contour[idx];
+            let before = -(point - contour[(idx + count - 1) % count])
+                .normalize_or_zero()
+                .perp();
+            let after = -(contour[(idx + 1) % count] - point)
+                .normalize_or_zero()
+                .perp();
+
+            let middle = (before + after).normalize_or_zero();
+            let cos = f32::max(middle.dot(before), 1.0 / LIMIT);
+            point + middle * distance / cos
+        })
+        .collect()
+}
+
+// Twice the area, positive when the contour runs clockwise on screen
+pub(crate) fn signed_area(contour: &[Vec2]) -> f32 {
+    (0..contour.len())
+        .map(|idx| contour[idx].perp_dot(contour[(idx + 1) % contour.len()]))
+        .sum()
+}
+
+pub(crate) fn bounds(points: impl IntoIterator<Item = Vec2>) -> BoundingBox {
+    let (min, max) = points.into_iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), point| (min.min(point), max.max(point)),
+    );
+
+    if min.x > max.x {
+        return BoundingBox::new(0.0, 0.0, 0.0, 0.0);
+    }
+
+    BoundingBox::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+// Box around the whole circle a round shape is cut from. Backgrounds stretched over it keep their
+// place however much of the circle the shape covers, and however it's turned.
+pub(crate) fn circle_bounds(center: Vec2, radius: f32) -> BoundingBox {
+    let radius = radius.abs();
+    BoundingBox::new(
+        center.x - radius,
+        center.y - radius,
+        radius * 2.0,
+        radius * 2.0,
+    )
+}
+
+// Fills contours with a background, stretching images and gradients over the bounding box. A
+// positive feather fades their edges out across that width, centered on them, instead of leaving
+// them aliased.
+pub(crate) fn draw(
+    batch: &mut RenderBatch,
+    contours: &[Vec<Vec2>],
+    rule: FillRule,
+    background: &Background,
+    bounds: BoundingBox,
+    feather: f32,
+) {
+    let solid = |triangles: &[[Vec2; 3]]| -> Vec<_> {
+        triangles
+            .iter()
+            .map(|triangle| triangle.map(|point| (point, 1.0)))
+            .collect()
+    };
+
+    // Convex shapes, like circles and rectangles, fan out from a corner instead of being swept,
+    // and any rule fills them the same
+    let convex = matches!(contours, [contour] if is_convex(contour));
+    let interior = |batch: &mut RenderBatch, contours: &[Vec<Vec2>]| {
+        if convex {
+            let contour = &contours[0];
+            let fan: Vec<_> = (1..contour.len() - 1)
+                .map(|idx| [contour[0], contour[idx], contour[idx + 1]])
+                .collect();
+            solid(&fan)
+        } else {
+            solid(batch.assets.fills.fill(contours, rule))
+        }
+    };
+
+    if feather <= 0.0 {
+        let triangles = interior(batch, contours);
+        paint(batch, &triangles, background, bounds);
+        return;
+    }
+
+    // Offsetting moves a contour away from its inside by winding, which is only sure to be the
+    // filled side for a convex one, so others are checked beside their longest edge
+    let half = feather / 2.0;
+    let outwards: Vec<_> = contours
+        .iter()
+        .map(|contour| {
+            if convex {
+                return half;
+            }
+
+            let count = contour.len();
+            let length = |idx: usize| contour[idx].distance(contour[(idx + 1) % count]);
+            let Some(idx) = (0..count).max_by(|&a, &b| length(a).total_cmp(&length(b))) else {
+                return half;
+            };
+
+            let (start, end) = (contour[idx], contour[(idx + 1) % count]);
+            let probe = (start + end) / 2.0 - (end - start).normalize_or_zero().perp() * half / 4.0;
+            if rule.inside(winding(contours, probe)) {
+                -half
+            } else {
+                half
+            }
+        })
+        .collect();
+
+    let insets: Vec<_> = contours
+        .iter()
+        .zip(&outwards)
+        .map(|(contour, outward)| offset(contour, -outward))
+        .collect();
+    let mut triangles = interior(batch, &insets);
+
+    // Strips from fully covered just inside each edge to uncovered just outside it
+    for ((contour, inset), outward) in contours.iter().zip(&insets).zip(&outwards) {
+        let outset = offset(contour, *outward);
+        let count = contour.len();
+        for idx in 0..count {
+            let next = (idx + 1) % count;
+            let (a, b) = ((inset[idx], 1.0), (inset[next], 1.0));
+            let (c, d) = ((outset[next], 0.0), (outset[idx], 0.0));
+            triangles.push([a, b, c]);
+            triangles.push([a, c, d]);
+        }
+    }
+
+    paint(batch, &triangles, background, bounds);
+}
+
+// Fills contours with a background, then covers the border of each one
+pub(crate) fn draw_bordered(
+    batch: &mut RenderBatch,
+    contours: &[Vec<Vec2>],
+    rule: FillRule,
+    background: &Background,
+    border: Border,
+    bounds: BoundingBox,
+    feather: f32,
+) {
+    draw(batch, contours, rule, background, bounds, feather);
+
+    let rings: Vec<_> = contours
+        .iter()
+        .flat_map(|contour| self::border(contour, border))
+        .collect();
+    draw(
+        batch,
+        &rings,
+        FillRule::NonZero,
+        &Background::Color(border.color),
+        bounds,
+        feather,
+    );
+}
+
+// Whether a contour turns the same way at every corner and goes around only once
+fn is_convex(contour: &[Vec2]) -> bool {
+    let count = contour.len();
+    if count < 3 {
+        return false;
+    }
+
+    let mut side = 0.0;
+    let mut turning = 0.0;
+    for idx in 0..count {
+        let point = contour[idx];
+        let incoming = (point - contour[(idx + count - 1) % count]).normalize_or_zero();
+        let outgoing = (contour[(idx + 1) % count] - point).normalize_or_zero();
+
+        let turn = incoming.perp_dot(outgoing);
+        if turn.abs() < EPSILON {
+            if incoming.dot(outgoing) < 0.0 {
+                return false;
+            }
+            continue;
+        }
+        if side * turn < 0.0 {
+            return false;
+        }
+
+        side = turn.signum();
+        turning += turn.atan2(incoming.dot(outgoing));
+    }
+
+    (turning.abs() - TAU).abs() < PI
+}
+
+// How many times the contours wind around a point, counting crossings to its right
+fn winding(contours: &[Vec<Vec2>], point: Vec2) -> i32 {
+    let mut winding = 0;
+    for contour in contours {
+        for idx in 0..contour.len() {
+            let (start, end) = (contour[idx], contour[(idx + 1) % contour.len()]);
+            if (start.y <= point.y) == (end.y <= point.y) {
+                continue;
+            }
+
+            let x = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+            if x > point.x {
+                winding += if end.y > start.y { 1 } else { -1 };
+            }
+        }
+    }
+    winding
+}
+
+// Triangle points come with how much of the background covers them, which scales every component
+// since colors are blended premultiplied
+pub(crate) fn paint(
+    batch: &mut RenderBatch,
+    triangles: &[[(Vec2, f32); 3]],
+    background: &Background,
+    bounds: BoundingBox,
+) {
+    match background {
+        Background::Color(color) => {
+            if *color == Color::CLEAR {
+                return;
+            }
+
+            let vertex = |&(point, coverage): &(Vec2, f32)| {
+                let color = Color::new(
+                    color.red * coverage,
+                    color.green * coverage,
+                    color.blue * coverage,
+                    color.alpha * coverage,
+                );
+                Vertex::new(point.x, point.y, 0.0, 0.0, color, u32::MAX)
+            };
+
+            for [a, b, c] in triangles {
+                batch.triangle(vertex(a), vertex(b), vertex(c));
+            }
+        }
+        Background::Image(asset) => {
+            let image = *batch.assets.images.get(asset.id);
+
+            // Clamped so feathered edges don't reach into neighbouring images in the atlas
+            let vertex = |&(point, coverage): &(Vec2, f32)| {
+                let u = ((point.x - bounds.x) / bounds.width.max(EPSILON)).clamp(0.0, 1.0);
+                let v = ((point.y - bounds.y) / bounds.height.max(EPSILON)).clamp(0.0, 1.0);
+                Vertex::new(
+                    point.x,
+                    point.y,
+                    image.u + u * image.width,
+                    image.v + v * image.height,
+                    Color::new(0.0, 0.0, 0.0, coverage),
+                    0,
+                )
+            };
+
+            for [a, b, c] in triangles {
+                batch.triangle(vertex(a), vertex(b), vertex(c));
+            }
+        }
+        Background::LinearGradient { stops, .. }
+        | Background::RadialGradient { stops, .. }
+        | Background::ConicGradient { stops, .. } => {
+            if stops.is_empty() {
+                return;
+            }
+
+            let Some(&ramp) = batch.assets.gradients.get(stops) else {
+                return;
+            };
+
+            let size = Vec2::new(bounds.width, bounds.height);
+            let origin = Vec2::new(bounds.x, bounds.y);
+
+            // Position in whatever space the shader expects for the kind of gradient
+            let (atlas_idx, position): (u32, Box<dyn Fn(Vec2) -> Vec2>) = match *background {
+                Background::LinearGradient { angle, .. } => {
+                    let direction = Vec2::from_angle(angle);
+                    let center = origin + size / 2.0;
+                    let length = (size.x * direction.x).abs() + (size.y * direction.y).abs();
+                    (
+                        2,
+                        Box::new(move |point| {
+                            let t = (point - center).dot(direction) / length.max(EPSILON);
+                            Vec2::new(t + 0.5, 0.0)
+                        }),
+                    )
+                }
+                Background::RadialGradient { center, radius, .. } => {
+                    let center = origin + Vec2::from(center) * size;
+                    let scale = (size / 2.0 * radius).max(Vec2::splat(EPSILON));
+                    (3, Box::new(move |point| (point - center) / scale))
+                }
+                // Turned back by the starting angle, so the shader can measure from +x
+                Background::ConicGradient { center, angle, .. } => {
+                    let center = origin + Vec2::from(center) * size;
+                    let rotation = Vec2::from_angle(-angle);
+                    (4, Box::new(move |point| rotation.rotate(point - center)))
+                }
+                Background::Color(_) | Background::Image(_) => unreachable!(),
+            };
+
+            let vertex = |&(point, coverage): &(Vec2, f32)| {
+                let uv = position(point);
+                let color = Color::new(ramp.u, ramp.v + ramp.height / 2.0, ramp.width, coverage);
+                Vertex::new(point.x, point.y, uv.x, uv.y, color, atlas_idx)
+            };
+
+            for [a, b, c] in triangles {
+                batch.triangle(vertex(a), vertex(b), vertex(c));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(triangles: &[[Vec2; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|[a, b, c]| (*b - *a).perp_dot(*c - *a).abs() / 2.0)
+            .sum()
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Vec2> {
+        vec![
+            Vec2::new(x, y),
+            Vec2::new(x + size, y),
+            Vec2::new(x + size, y + size),
+            Vec2::new(x, y + size),
+        ]
+    }
+
+    fn assert_close(actual: f32, expected: f32, fraction: f32) {
+        assert!(
+            (actual - expected).abs() <= expected * fraction,
+            "{} is not within {} of {}",
+            actual,
+            fraction,
+            expected
+        );
+    }
+
+    #[test]
+    fn square_fills_under_both_rules() {
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_close(area(&fill(&[square(0.0, 0.0, 10.0)], rule)), 100.0, 1e-4);
+        }
+    }
+
+    #[test]
+    fn holes() {
+        let outer = square(0.0, 0.0, 10.0);
+        let mut inner = square(2.0, 2.0, 4.0);
+
+        // Winding the same way as the outside only leaves a hole under even-odd
+        assert_close(
+            area(&fill(&[outer.clone(), inner.clone()], FillRule::EvenOdd)),
+            84.0,
+            1e-4,
+        );
+        assert_close(
+            area(&fill(&[outer.clone(), inner.clone()], FillRule::NonZero)),
+            100.0,
+            1e-4,
+        );
+
+        inner.reverse();
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert_close(
+                area(&fill(&[outer.clone(), inner.clone()], rule)),
+                84.0,
+                1e-4,
+            );
+        }
+    }
+
+    #[test]
+    fn pentagram() {
+        let points: Vec<_> = (0..5)
+            .map(|idx| Vec2::from_angle(idx as f32 * 2.0 * TAU / 5.0) * 100.0)
+            .collect();
+
+        // The pentagon in the middle is wound twice, so even-odd leaves it out
+        let tip = Vec2::from_angle(0.0) * 100.0;
+        let inner = 100.0 * (2.0 * PI / 5.0).cos() / (PI / 5.0).cos();
+        let pentagon = 5.0 / 2.0 * inner * inner * (TAU / 5.0).sin();
+        let star = 5.0 * tip.perp_dot(Vec2::from_angle(PI / 5.0) * inner).abs();
+
+        assert_close(
+            area(&fill(&[points.clone()], FillRule::NonZero)),
+            star,
+            1e-3,
+        );
+        assert_close(
+            area(&fill(&[points], FillRule::EvenOdd)),
+            star - pentagon,
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn circle() {
+        for radius in [200.0, 400.0] {
+            let center = Vec2::new(radius, radius);
+            let mut contour = Vec::new();
+            arc(center, radius, 0.0, TAU, TOLERANCE, &mut contour);
+
+            assert_close(
+                area(&fill(&[contour], FillRule::NonZero)),
+                PI * radius * radius,
+                1e-3,
+            );
+        }
+    }
+
+    #[test]
+    fn pie() {
+        let (radius, sweep) = (600.0, 2.0);
+        let center = Vec2::new(radius, radius);
+        let mut contour = vec![center, center + Vec2::X * radius];
+        arc(center, radius, 0.0, sweep, TOLERANCE, &mut contour);
+
+        assert_close(
+            area(&fill(&[contour], FillRule::NonZero)),
+            sweep * radius * radius / 2.0,
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn cache_keeps_drawn_fills() {
+        let mut cache = FillCache::default();
+        let contours = [square(0.0, 0.0, 10.0)];
+
+        assert_close(area(cache.fill(&contours, FillRule::NonZero)), 100.0, 1e-4);
+        cache.sweep();
+        assert_eq!(cache.fills.len(), 1);
+        cache.sweep();
+        assert!(cache.fills.is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use fontdue::{Metrics, OutlineBounds};
+use owned_ttf_parser::{AsFaceRef, OwnedFace};
+use ttf_parser::{GlyphId, OutlineBuilder, Tag};
+
+use super::font::Variation;
+
+const WEIGHT: Tag = Tag::from_bytes(b"wght");
+const WIDTH: Tag = Tag::from_bytes(b"wdth");
+
+// Font with variation axes, outlined through ttf-parser at the requested weight and width since
+// fontdue only ever draws the default instance
+#[derive(Debug)]
+pub struct VariableFace {
+    face: OwnedFace,
+    weights: Option<(f32, f32)>,
+}
+
+impl VariableFace {
+    // None for static fonts, or ones that can't be parsed
+    pub(crate) fn parse(bytes: &[u8], index: u32) -> Option<Self> {
+        let face = OwnedFace::from_vec(bytes.to_vec(), index).ok()?;
+        let parsed = face.as_face_ref();
+        if !parsed.is_variable() {
+            return None;
+        }
+
+        let weights = parsed
+            .variation_axes()
+            .into_iter()
+            .find(|axis| axis.tag == WEIGHT)
+            .map(|axis| (axis.min_value, axis.max_value));
+
+        Some(Self { face, weights })
+    }
+
+    pub(crate) fn covers(&self, weight: u16) -> bool {
+        self.weights
+            .is_some_and(|(min, max)| (min..=max).contains(&(weight as f32)))
+    }
+
+    // Copies the parsed face, which only holds offsets into the font data, to vary it
+    fn face(&self, variation: Variation) -> ttf_parser::Face<'_> {
+        let mut face = self.face.as_face_ref().clone();
+
+        // Values outside an axis are clamped, and missing axes are ignored
+        face.set_variation(WEIGHT, variation.weight);
+        face.set_variation(WIDTH, variation.width);
+        face
+    }
+
+    pub(crate) fn has_glyph(&self, character: char) -> bool {
+        self.face.as_face_ref().glyph_index(character).is_some()
+    }
+
+    pub(crate) fn ascent(&self, size: f32) -> f32 {
+        let face = self.face.as_face_ref();
+        face.ascender() as f32 * size / face.units_per_em() as f32
+    }
+
+    pub(crate) fn metrics(&self, character: char, size: f32, variation: Variation) -> Metrics {
+        self.outline(character, size, variation).0
+    }
+
+    pub(crate) fn rasterize(
+        &self,
+        character: char,
+        size: f32,
+        variation: Variation,
+    ) -> (Metrics, Vec<u8>) {
+        let (metrics, outline) = self.outline(character, size, variation);
+
+        let mut rasterizer = Rasterizer::new(metrics.width, metrics.height);
+        for segment in outline.segments {
+            match segment {
+                Segment::Line(a, b) => rasterizer.draw_line(a, b),
+                Segment::Quad(a, b, c) => rasterizer.draw_quad(a, b, c),
+                Segment::Cubic(a, b, c, d) => rasterizer.draw_cubic(a, b, c, d),
+            }
+        }
+
+        let mut bitmap = vec![0; metrics.width * metrics.height];
+        rasterizer.for_each_pixel(|idx, coverage| {
+            bitmap[idx] = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+        });
+
+        (metrics, bitmap)
+    }
+
+    // Segments come out already in bitmap space, with y pointing down from the top row
+    fn outline(&self, character: char, size: f32, variation: Variation) -> (Metrics, Outline) {
+        let face = self.face(variation);
+        let scale = size / face.units_per_em() as f32;
+        let id = face.glyph_index(character).unwrap_or(GlyphId(0));
+
+        let mut outline = Outline::default();
+        let bounds = face.outline_glyph(id, &mut outline);
+        let advance_width = face.glyph_hor_advance(id).unwrap_or(0) as f32 * scale;
+
+        let Some(bounds) = bounds else {
+            let metrics = Metrics {
+                advance_width,
+                ..Metrics::default()
+            };
+            return (metrics, Outline::default());
+        };
+
+        let xmin = (bounds.x_min as f32 * scale).floor();
+        let ymin = (bounds.y_min as f32 * scale).floor();
+        let xmax = (bounds.x_max as f32 * scale).ceil();
+        let ymax = (bounds.y_max as f32 * scale).ceil();
+
+        let place = |p: Point| point(p.x * scale - xmin, ymax - p.y * scale);
+        outline.segments = outline
+            .segments
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Line(a, b) => Segment::Line(place(a), place(b)),
+                Segment::Quad(a, b, c) => Segment::Quad(place(a), place(b), place(c)),
+                Segment::Cubic(a, b, c, d) => {
+                    Segment::Cubic(place(a), place(b), place(c), place(d))
+                }
+            })
+            .collect();
+
+        let metrics = Metrics {
+            xmin: xmin as i32,
+            ymin: ymin as i32,
+            width: (xmax - xmin) as usize,
+            height: (ymax - ymin) as usize,
+            advance_width,
+            advance_height: 0.0,
+            bounds: OutlineBounds {
+                xmin: bounds.x_min as f32 * scale,
+                ymin: bounds.y_min as f32 * scale,
+                width: bounds.width() as f32 * scale,
+                height: bounds.height() as f32 * scale,
+            },
+        };
+
+        (metrics, outline)
+    }
+}
+
+enum Segment {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+}
+
+// Collects a glyph outline in font units
+#[derive(Default)]
+struct Outline {
+    segments: Vec<Segment>,
+    start: Point,
+    last: Point,
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = point(x, y);
+        self.segments.push(Segment::Line(self.last, to));
+        self.last = to;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let to = point(x, y);
+        self.segments
+            .push(Segment::Quad(self.last, point(x1, y1), to));
+        self.last = to;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let to = point(x, y);
+        self.segments
+            .push(Segment::Cubic(self.last, point(x1, y1), point(x2, y2), to));
+        self.last = to;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.segments.push(Segment::Line(self.last, self.start));
+        }
+        self.last = self.start;
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
 use winit::{
-    event::{KeyEvent, WindowEvent},
-    keyboard::PhysicalKey,
+    event::{Ime, KeyEvent, WindowEvent},
+    keyboard::{ModifiersState, PhysicalKey},
 };
 
 use super::tracker::Tracker;
@@ -10,28 +10,58 @@ use super::tracker::Tracker;
 #[derive(Debug, Clone)]
 pub struct Keyboard {
     keys: HashMap<PhysicalKey, KeyEvent>,
+    presses: Vec<KeyEvent>,
+    ime: Vec<Ime>,
+    modifiers: ModifiersState,
 }
 
 impl Keyboard {
     pub fn new() -> Keyboard {
         Keyboard {
             keys: HashMap::new(),
+            presses: Vec::new(),
+            ime: Vec::new(),
+            modifiers: ModifiersState::empty(),
         }
     }
 
+    // Every event is followed by an update, so only the latest event's presses are kept
     pub fn update(&mut self, event: &WindowEvent) {
+        self.presses.clear();
+        self.ime.clear();
+
         match event {
             WindowEvent::KeyboardInput { event, .. } => {
                 if event.state.is_pressed() {
                     self.keys.insert(event.physical_key, event.clone());
+                    self.presses.push(event.clone());
                 } else {
                     self.keys.remove(&event.physical_key);
                 }
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::Ime(ime) => {
+                self.ime.push(ime.clone());
+            }
             _ => {}
         }
     }
 
+    // Unlike just_pressed, includes key repeats
+    pub fn presses(&self) -> &[KeyEvent] {
+        &self.presses
+    }
+
+    pub fn ime(&self) -> &[Ime] {
+        &self.ime
+    }
+
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
     pub fn is_pressed(&self, key: PhysicalKey) -> bool {
         self.keys.contains_key(&key)
     }
@@ -13,6 +13,9 @@ struct VertexOutput {
 }
 
 const u32_max = 4294967295u;
+const tau = 6.28318530718;
+// Matches RAMP_WIDTH in gradient.rs
+const ramp_width = 256.0;
 
 @group(0) @binding(0) var<uniform> transformation: mat4x4<f32>;
 
@@ -33,14 +36,45 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 @group(2) @binding(0) var font_atlas: texture_2d<f32>;
 @group(2) @binding(1) var font_sampler: sampler;
 
+@group(3) @binding(0) var gradient_atlas: texture_2d<f32>;
+@group(3) @binding(1) var gradient_sampler: sampler;
+
+// The ramp's u, v through the middle of its row, and width, all in atlas uv units. Neighbouring
+// texels are blended here since the atlas is sampled with nearest filtering.
+fn gradient(t: f32, ramp: vec3<f32>) -> vec4<f32> {
+    let texel = clamp(t, 0.0, 1.0) * (ramp_width - 1.0);
+    let first = floor(texel);
+    let second = min(first + 1.0, ramp_width - 1.0);
+    let spacing = ramp.z / ramp_width;
+
+    let low_uv = vec2<f32>(ramp.x + (first + 0.5) * spacing, ramp.y);
+    let high_uv = vec2<f32>(ramp.x + (second + 0.5) * spacing, ramp.y);
+
+    let low = textureSampleLevel(gradient_atlas, gradient_sampler, low_uv, 0.0);
+    let high = textureSampleLevel(gradient_atlas, gradient_sampler, high_uv, 0.0);
+    return mix(low, high, texel - first);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     switch in.atlas_idx {
         case 0u: {
-            return textureSample(image_atlas, image_sampler, in.uv);
+            return textureSample(image_atlas, image_sampler, in.uv) * in.color.a;
         }
         case 1u: {
-            return textureSample(font_atlas, font_sampler, in.uv);
+            return textureSample(font_atlas, font_sampler, in.uv) * in.color.a;
+        }
+        // Linear, with uv.x already the position along it. Gradients keep their coverage in color.w.
+        case 2u: {
+            return gradient(in.uv.x, in.color.xyz) * in.color.w;
+        }
+        // Radial, with uv scaled so the radius is 1
+        case 3u: {
+            return gradient(length(in.uv), in.color.xyz) * in.color.w;
+        }
+        // Conic, with uv relative to the center and turned so the gradient starts at +x
+        case 4u: {
+            return gradient(fract(atan2(in.uv.y, in.uv.x) / tau), in.color.xyz) * in.color.w;
         }
         default: {
             return in.color;
@@ -2,7 +2,8 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use depict::{
     builtin::{
-        circle::Circle, ellipse::Ellipse, rectangle::Rectangle, text::Text, triangle::Triangle,
+        circle::Circle, ellipse::Ellipse, rectangle::Rectangle, text::Text, text_input::TextInput,
+        triangle::Triangle,
     },
     core::{
         context::{Context, PartialContext},
@@ -17,7 +18,6 @@ use depict::{
         font::{FontEmphasis, FontThickness},
     },
 };
-use winit::keyboard::{KeyCode, PhysicalKey};
 
 struct Game {
     dirt: Asset<Image>,
@@ -27,7 +27,7 @@ struct Game {
     roboto: Asset<Font>,
 
     text_pos: f32,
-    typed: String,
+    input: Option<TextInput>,
     circle_size: f32,
 }
 
@@ -100,21 +100,18 @@ impl<'a> System<'a> for Game {
         self.text_pos = ctx.size.width / 2.0 + f32::sin(ctx.time.seconds() as f32) * 100.0;
         self.circle_size = (f32::cos(ctx.time.seconds() as f32 * 0.5) + 1.0) * 200.0 + 25.0;
 
-        for key in ctx.keyboard.just_pressed() {
-            match key.physical_key {
-                PhysicalKey::Code(KeyCode::Enter) => {
-                    self.typed += "\n";
-                }
-                PhysicalKey::Code(KeyCode::Backspace) => {
-                    self.typed.pop();
-                }
-                _ => {
-                    if let Some(text) = &key.text {
-                        self.typed += text;
-                    }
-                }
-            }
-        }
+        let input = self.input.get_or_insert_with(|| {
+            TextInput::new(Text::new(0.0, 0.0, String::new(), self.roboto))
+                .with_multiline(true)
+                .with_caret_color(Color::WHITE)
+        });
+        input.set_style(
+            Text::new(0.0, 0.0, String::new(), self.roboto)
+                .with_color(Color::WHITE)
+                .with_width(ctx.size.width)
+                .with_max_height(200.0),
+        );
+        input.update(ctx);
     }
 
     fn render(&mut self, ctx: &mut Context) {
@@ -144,11 +141,9 @@ impl<'a> System<'a> for Game {
                 .with_background(Background::Image(self.brick)),
         ]);
 
-        ctx.draw(
-            Text::new(0.0, 0.0, self.typed.clone(), self.roboto)
-                .with_color(Color::WHITE)
-                .with_width(ctx.size.width),
-        );
+        if let Some(input) = &self.input {
+            input.render(ctx);
+        }
 
         ctx.draw(
             Text::new(
@@ -166,7 +161,10 @@ impl<'a> System<'a> for Game {
             .with_thickness(FontThickness::Bold),
         );
 
-        ctx.draw(Triangle::new(100.0, 100.0, 50.0, 200.0, 150.0, 200.0).with_color(Color::RED));
+        ctx.draw(
+            Triangle::new(100.0, 100.0, 50.0, 200.0, 150.0, 200.0)
+                .with_background(Background::Color(Color::RED)),
+        );
 
         ctx.draw(
             Circle::new(
@@ -174,7 +172,7 @@ impl<'a> System<'a> for Game {
                 ctx.size.height / 2.0,
                 self.circle_size,
             )
-            .with_color(Color::GREEN),
+            .with_background(Background::Color(Color::GREEN)),
         );
 
         ctx.draw(
@@ -184,7 +182,7 @@ impl<'a> System<'a> for Game {
                 (f32::sin(ctx.time.seconds() as f32) + 1.1) * 100.0,
                 (f32::cos(ctx.time.seconds() as f32 * 2.0 + 2.0) + 1.1) * 100.0,
             )
-            .with_color(Color::MAGENTA),
+            .with_background(Background::Color(Color::MAGENTA)),
         );
     }
 }
@@ -200,7 +198,7 @@ fn main() {
             roboto: Asset::default(),
 
             text_pos: 0.0,
-            typed: "".to_string(),
+            input: None,
             circle_size: 0.0,
         })),
     );